@@ -0,0 +1,674 @@
+use bevy::{
+    asset::AssetPath,
+    ecs::system::{RunSystemOnce, SystemParam},
+    gltf::{GltfMaterialExtras, GltfMeshExtras, GltfSceneExtras},
+    prelude::*,
+    reflect::{serde::ReflectDeserializer, TypeRegistration, TypeRegistry},
+    scene::ron,
+    state::state::FreelyMutableState,
+    utils::Parallel,
+};
+use serde::de::DeserializeSeed;
+
+mod error;
+mod json;
+mod lookup;
+mod presets;
+mod scenes;
+mod schema;
+#[cfg(test)]
+mod test_utils;
+mod validation;
+mod writer;
+
+pub use error::*;
+pub use json::*;
+pub use lookup::*;
+pub use presets::*;
+pub use scenes::*;
+pub use schema::*;
+pub use validation::*;
+pub use writer::*;
+
+// 启动时加载 scene_paths 中的所有场景，全部处理完成后切换到 processed 状态
+// 需要按关卡等按需加载场景时，使用 BlenderScenes::request
+pub struct BlenderEditorPlugin<T: States + FreelyMutableState> {
+    pub scene_paths: Vec<AssetPath<'static>>,
+    pub processed: T,
+}
+
+impl<T: States + FreelyMutableState> BlenderEditorPlugin<T> {
+    pub fn new(scene_paths: Vec<AssetPath<'static>>, processed: T) -> Self {
+        Self {
+            scene_paths,
+            processed,
+        }
+    }
+}
+
+impl<T: States + FreelyMutableState> Plugin for BlenderEditorPlugin<T> {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<BlenderScenesPlugin>() {
+            app.add_plugins(BlenderScenesPlugin);
+        }
+        let asset_server = app.world().resource::<AssetServer>().clone();
+        let handles: Vec<Handle<Scene>> = self
+            .scene_paths
+            .iter()
+            .map(|path| asset_server.load(path))
+            .collect();
+        let mut requested = app.world_mut().resource_mut::<RequestedScenes>();
+        for handle in &handles {
+            requested.track(handle.clone());
+        }
+        app.insert_resource(SceneHandles(handles)).add_systems(
+            Update,
+            enter_processed_state(self.processed.clone())
+                .run_if(scene_handles_processed.and_then(run_once()))
+                .after(BlenderScenesSet),
+        );
+    }
+}
+
+// BlenderEditorPlugin 启动时加载的场景
+#[derive(Resource)]
+pub struct SceneHandles(pub Vec<Handle<Scene>>);
+
+// SceneHandles 中的场景是否都已处理完成
+fn scene_handles_processed(
+    scene_handles: Res<SceneHandles>,
+    requested: Res<RequestedScenes>,
+) -> bool {
+    scene_handles
+        .0
+        .iter()
+        .all(|handle| requested.is_processed(handle.id()))
+}
+
+// 切换到场景处理完成的状态
+fn enter_processed_state<T: States + FreelyMutableState>(
+    processed: T,
+) -> impl FnMut(ResMut<NextState<T>>) {
+    move |mut next_state| {
+        next_state.set(processed.clone());
+    }
+}
+
+// 反射得到的组件和它的类型注册
+pub type ReflectedComponent = (Box<dyn Reflect>, TypeRegistration);
+
+// extras 中的一个键对实体的操作
+// "@preset" 插入预设，"-Visibility" 删除组件，"#Enemy" 插入空的标记组件，其他键插入反射组件
+pub enum ExtrasAction {
+    Preset(String, ExtrasPreset),
+    Insert(ReflectedComponent),
+    Remove(TypeRegistration),
+}
+
+impl ExtrasAction {
+    // 应用顺序：先插入预设，再插入组件覆盖预设，最后删除组件
+    fn order(&self) -> u8 {
+        match self {
+            Self::Preset(..) => 0,
+            Self::Insert(_) => 1,
+            Self::Remove(_) => 2,
+        }
+    }
+
+    // 将操作应用到实体
+    pub fn apply(&self, entity_mut: &mut EntityWorldMut, type_registry: &TypeRegistry) {
+        match self {
+            Self::Preset(_, preset) => preset(entity_mut),
+            Self::Insert((component, type_registration)) => {
+                if let Some(reflect_component) = type_registration.data::<ReflectComponent>() {
+                    reflect_component.insert(entity_mut, component.as_reflect(), type_registry);
+                }
+            }
+            Self::Remove(type_registration) => {
+                if let Some(reflect_component) = type_registration.data::<ReflectComponent>() {
+                    reflect_component.remove(entity_mut);
+                }
+            }
+        }
+    }
+}
+
+// 反射 extras 时使用的类型注册表、键别名、预设和校验设置
+#[derive(Clone)]
+pub struct ExtrasReflectContext {
+    pub app_type_registry: AppTypeRegistry,
+    pub aliases: ExtrasAliases,
+    pub presets: ExtrasPresets,
+    pub settings: ExtrasValidationSettings,
+}
+
+impl ExtrasReflectContext {
+    pub fn new(
+        app_type_registry: AppTypeRegistry,
+        aliases: ExtrasAliases,
+        presets: ExtrasPresets,
+    ) -> Self {
+        Self {
+            app_type_registry,
+            aliases,
+            presets,
+            settings: ExtrasValidationSettings::default(),
+        }
+    }
+
+    pub fn with_settings(mut self, settings: ExtrasValidationSettings) -> Self {
+        self.settings = settings;
+        self
+    }
+}
+
+impl FromWorld for ExtrasReflectContext {
+    fn from_world(world: &mut World) -> Self {
+        Self::new(
+            world.resource::<AppTypeRegistry>().clone(),
+            world
+                .get_resource::<ExtrasAliases>()
+                .cloned()
+                .unwrap_or_default(),
+            world
+                .get_resource::<ExtrasPresets>()
+                .cloned()
+                .unwrap_or_default(),
+        )
+        .with_settings(
+            world
+                .get_resource::<ExtrasValidationSettings>()
+                .cloned()
+                .unwrap_or_default(),
+        )
+    }
+}
+
+// 在系统中获取 ExtrasReflectContext
+#[derive(SystemParam)]
+pub struct ExtrasReflectParam<'w> {
+    app_type_registry: Res<'w, AppTypeRegistry>,
+    aliases: Res<'w, ExtrasAliases>,
+    presets: Res<'w, ExtrasPresets>,
+    settings: Res<'w, ExtrasValidationSettings>,
+}
+
+impl ExtrasReflectParam<'_> {
+    pub fn context(&self) -> ExtrasReflectContext {
+        ExtrasReflectContext::new(
+            self.app_type_registry.clone(),
+            self.aliases.clone(),
+            self.presets.clone(),
+        )
+        .with_settings(self.settings.clone())
+    }
+}
+
+// 将场景中所有 glTF extras 反射为组件，并插入到对应的实体，返回反射失败的错误
+pub fn reflect_scene_extras(
+    scene: &mut Scene,
+    context: ExtrasReflectContext,
+) -> Vec<ExtrasReflectError> {
+    reflect_scene_extras_report(scene, context).errors
+}
+
+// 与 reflect_scene_extras 相同，返回完整的校验结果
+pub fn reflect_scene_extras_report(
+    scene: &mut Scene,
+    context: ExtrasReflectContext,
+) -> ExtrasReport {
+    scene
+        .world
+        .run_system_once(insert_reflect_components(context))
+}
+
+// 插入反射组件
+pub fn insert_reflect_components(
+    context: ExtrasReflectContext,
+) -> impl FnMut(
+    ParallelCommands,
+    Query<(
+        Entity,
+        Option<&Name>,
+        Option<&GltfSceneExtras>,
+        Option<&GltfExtras>,
+        Option<&GltfMeshExtras>,
+        Option<&GltfMaterialExtras>,
+    )>,
+) -> ExtrasReport {
+    move |commands, gltf_extras| {
+        let mut reports = Parallel::<ExtrasReport>::default();
+        gltf_extras.par_iter().for_each(
+            |(extras_entity, name, scene_extras, extras, mesh_extras, material_extras)| {
+                let mut extras_vec: Vec<(ExtrasSource, &str)> = Vec::new();
+                if let Some(GltfSceneExtras { value }) = scene_extras {
+                    extras_vec.push((ExtrasSource::Scene, value));
+                }
+                if let Some(GltfExtras { value }) = extras {
+                    extras_vec.push((ExtrasSource::Node, value));
+                }
+                if let Some(GltfMeshExtras { value }) = mesh_extras {
+                    extras_vec.push((ExtrasSource::Mesh, value));
+                }
+                if let Some(GltfMaterialExtras { value }) = material_extras {
+                    extras_vec.push((ExtrasSource::Material, value));
+                }
+                if extras_vec.is_empty() {
+                    return;
+                }
+                let mut reflect_components_of_extras =
+                    reflect_components_of_extras(extras_entity, name.cloned(), context.clone());
+                let mut actions = Vec::new();
+                let mut reflect_errors = Vec::new();
+                let mut inserted = Vec::new();
+                for (source, extras) in extras_vec {
+                    for result in reflect_components_of_extras(extras) {
+                        match result {
+                            Ok(action) => {
+                                if let ExtrasAction::Insert((_, type_registration)) = &action {
+                                    inserted.push((
+                                        type_registration.type_id(),
+                                        type_registration.type_info().type_path(),
+                                        source,
+                                    ));
+                                }
+                                actions.push(action);
+                            }
+                            Err(error) => reflect_errors.push(error),
+                        }
+                    }
+                }
+                // 有没被忽略的键，但是没有插入任何组件或预设
+                let empty = (!actions.is_empty() || !reflect_errors.is_empty())
+                    && actions
+                        .iter()
+                        .all(|action| matches!(action, ExtrasAction::Remove(_)));
+                reports.borrow_local_mut().add_entity(
+                    extras_entity,
+                    name,
+                    &inserted,
+                    reflect_errors,
+                    empty,
+                );
+                if actions.is_empty() {
+                    return;
+                }
+                actions.sort_by_key(ExtrasAction::order);
+                let type_registry = context.app_type_registry.clone();
+                commands.command_scope(move |mut commands| {
+                    commands.add(move |world: &mut World| {
+                        let type_registry = type_registry.read();
+                        let mut entity_mut = world.entity_mut(extras_entity);
+                        for action in &actions {
+                            action.apply(&mut entity_mut, &type_registry);
+                        }
+                    });
+                });
+            },
+        );
+        let mut report = ExtrasReport::default();
+        for local_report in reports.iter_mut() {
+            report.extend(std::mem::take(local_report));
+        }
+        report
+    }
+}
+
+// 反射多个组件
+pub fn reflect_components_of_extras(
+    extras_entity: Entity,
+    name: Option<Name>,
+    context: ExtrasReflectContext,
+) -> impl FnMut(&str) -> Vec<Result<ExtrasAction, ExtrasReflectError>> {
+    move |extras| {
+        let json_map = match serde_json::from_str::<serde_json::Map<_, _>>(extras) {
+            Ok(map) => map,
+            Err(error) => {
+                return vec![Err(ExtrasReflectError::JsonParse {
+                    entity: extras_entity,
+                    name: name.clone(),
+                    extras: extras.into(),
+                    message: error.to_string(),
+                })];
+            }
+        };
+        let type_registry = context.app_type_registry.read();
+        let mut reflect_component = reflect_component(
+            extras_entity,
+            name.as_ref(),
+            &type_registry,
+            &context.aliases,
+        );
+        let mut actions = Vec::new();
+        for (key, value) in json_map {
+            if context.settings.ignored_keys.contains(&key) {
+                continue;
+            }
+            if key == PRESET_KEY {
+                actions.extend(preset_actions(
+                    extras_entity,
+                    name.as_ref(),
+                    &context.presets,
+                    key,
+                    value,
+                ));
+            } else if let Some(type_key) = key.strip_prefix(REMOVE_PREFIX) {
+                actions.push(
+                    component_registration(
+                        extras_entity,
+                        name.as_ref(),
+                        &type_registry,
+                        &context.aliases,
+                        type_key,
+                        &key,
+                        &value,
+                    )
+                    .map(|type_registration| ExtrasAction::Remove(type_registration.clone())),
+                );
+            } else if let Some(type_key) = key.strip_prefix(TAG_PREFIX) {
+                actions.push(
+                    tag_component(
+                        extras_entity,
+                        name.as_ref(),
+                        &type_registry,
+                        &context.aliases,
+                        type_key,
+                        &key,
+                        &value,
+                    )
+                    .map(ExtrasAction::Insert),
+                );
+            } else {
+                actions.push(reflect_component((key, value)).map(ExtrasAction::Insert));
+            }
+        }
+        actions
+    }
+}
+
+// 插入预设的键
+pub const PRESET_KEY: &str = "@preset";
+// 删除组件的键前缀
+pub const REMOVE_PREFIX: &str = "-";
+// 插入空标记组件的键前缀
+pub const TAG_PREFIX: &str = "#";
+
+// 预设的值可以是一个预设名，也可以是预设名数组
+fn preset_actions(
+    extras_entity: Entity,
+    name: Option<&Name>,
+    presets: &ExtrasPresets,
+    key: String,
+    value: serde_json::Value,
+) -> Vec<Result<ExtrasAction, ExtrasReflectError>> {
+    let preset_names = match &value {
+        serde_json::Value::String(preset_name) => vec![Some(preset_name.as_str())],
+        serde_json::Value::Array(preset_names) => {
+            preset_names.iter().map(serde_json::Value::as_str).collect()
+        }
+        _ => vec![None],
+    };
+    preset_names
+        .into_iter()
+        .map(|preset_name| {
+            let preset = preset_name.and_then(|preset_name| presets.0.get_key_value(preset_name));
+            match preset {
+                Some((preset_name, preset)) => {
+                    Ok(ExtrasAction::Preset(preset_name.clone(), preset.clone()))
+                }
+                None => Err(ExtrasReflectError::UnknownPreset {
+                    entity: extras_entity,
+                    name: name.cloned(),
+                    key: key.clone(),
+                    value: preset_name.map_or_else(|| value.to_string(), Into::into),
+                }),
+            }
+        })
+        .collect()
+}
+
+// 查找键对应的组件类型注册，type_key 是去掉前缀后的键
+fn component_registration<'a>(
+    extras_entity: Entity,
+    name: Option<&Name>,
+    type_registry: &'a TypeRegistry,
+    aliases: &ExtrasAliases,
+    type_key: &str,
+    key: &str,
+    value: &serde_json::Value,
+) -> Result<&'a TypeRegistration, ExtrasReflectError> {
+    let type_registration = match lookup_type_registration(type_key, type_registry, aliases) {
+        Ok(type_registration) => type_registration,
+        Err(ExtrasLookupError::Unknown) => {
+            return Err(ExtrasReflectError::UnknownType {
+                entity: extras_entity,
+                name: name.cloned(),
+                key: key.into(),
+                value: value_to_string(value.clone()),
+            });
+        }
+        Err(ExtrasLookupError::Ambiguous(candidates)) => {
+            return Err(ExtrasReflectError::AmbiguousType {
+                entity: extras_entity,
+                name: name.cloned(),
+                key: key.into(),
+                value: value_to_string(value.clone()),
+                candidates,
+            });
+        }
+    };
+    if type_registration.data::<ReflectComponent>().is_none() {
+        return Err(ExtrasReflectError::MissingReflectComponent {
+            entity: extras_entity,
+            name: name.cloned(),
+            key: key.into(),
+            value: value_to_string(value.clone()),
+            type_path: type_registration.type_info().type_path().into(),
+        });
+    }
+    Ok(type_registration)
+}
+
+// 生成标记组件，有 ReflectDefault 时使用默认值，否则视为没有字段的结构体，值会被忽略
+fn tag_component(
+    extras_entity: Entity,
+    name: Option<&Name>,
+    type_registry: &TypeRegistry,
+    aliases: &ExtrasAliases,
+    type_key: &str,
+    key: &str,
+    value: &serde_json::Value,
+) -> Result<ReflectedComponent, ExtrasReflectError> {
+    let type_registration = component_registration(
+        extras_entity,
+        name,
+        type_registry,
+        aliases,
+        type_key,
+        key,
+        value,
+    )?;
+    if let Some(reflect_default) = type_registration.data::<ReflectDefault>() {
+        return Ok((reflect_default.default(), type_registration.clone()));
+    }
+    match deserialize_component(type_registration, type_registry, "()") {
+        Ok(component) => Ok((component, type_registration.clone())),
+        Err(message) => Err(ExtrasReflectError::Ron {
+            entity: extras_entity,
+            name: name.cloned(),
+            key: key.into(),
+            value: value_to_string(value.clone()),
+            message,
+        }),
+    }
+}
+
+// 反射组件
+pub fn reflect_component<'a>(
+    extras_entity: Entity,
+    name: Option<&'a Name>,
+    type_registry: &'a TypeRegistry,
+    aliases: &'a ExtrasAliases,
+) -> impl FnMut((String, serde_json::Value)) -> Result<ReflectedComponent, ExtrasReflectError> + 'a
+{
+    move |(key, value)| {
+        let type_registration = component_registration(
+            extras_entity,
+            name,
+            type_registry,
+            aliases,
+            &key,
+            &key,
+            &value,
+        )?;
+        // 字符串直接作为 RON，其他 JSON 值按照类型信息转换为 RON
+        let (value, ron_value) = match value {
+            serde_json::Value::String(value) => (value.clone(), value),
+            value => match json_to_ron(&value, type_registration.type_info(), type_registry) {
+                Ok(ron_value) => (value.to_string(), ron_value),
+                Err(message) => {
+                    return Err(ExtrasReflectError::NonStringValue {
+                        entity: extras_entity,
+                        name: name.cloned(),
+                        key,
+                        value: value.to_string(),
+                        message,
+                    });
+                }
+            },
+        };
+        match deserialize_component(type_registration, type_registry, &ron_value) {
+            Ok(component) => Ok((component, type_registration.clone())),
+            Err(message) => Err(ExtrasReflectError::Ron {
+                entity: extras_entity,
+                name: name.cloned(),
+                key,
+                value,
+                message,
+            }),
+        }
+    }
+}
+
+// 使用 RON 反序列化组件
+fn deserialize_component(
+    type_registration: &TypeRegistration,
+    type_registry: &TypeRegistry,
+    ron_value: &str,
+) -> Result<Box<dyn Reflect>, String> {
+    let type_path = type_registration.type_info().type_path();
+    let ron_string = format!("{{\"{type_path}\":{ron_value}}}");
+    let reflect_deserializer = ReflectDeserializer::new(type_registry);
+    let mut deserializer =
+        ron::Deserializer::from_str(&ron_string).map_err(|error| error.to_string())?;
+    reflect_deserializer
+        .deserialize(&mut deserializer)
+        .map_err(|error| error.to_string())
+}
+
+// 错误中记录的原始值，字符串不带引号
+fn value_to_string(value: serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(value) => value,
+        value => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{test_utils::*, *};
+
+    #[test]
+    fn reflects_components_from_extras() {
+        let (mut scene, entity) = scene_with_extras(
+            r#"{"ShadowsEnabled": "(true)", "Collider": "Cuboid((x: 1.0, y: 2.0, z: 3.0))"}"#,
+        );
+        let errors = reflect_scene_extras(&mut scene, context());
+
+        assert!(errors.is_empty());
+        let entity = scene.world.entity(entity);
+        assert_eq!(entity.get::<ShadowsEnabled>(), Some(&ShadowsEnabled(true)));
+        assert_eq!(
+            entity.get::<Collider>(),
+            Some(&Collider::Cuboid(Vec3::new(1., 2., 3.)))
+        );
+    }
+
+    #[test]
+    fn merges_extras_from_every_source() {
+        let mut world = scene_world();
+        let entity = world
+            .spawn((
+                GltfExtras {
+                    value: r#"{"ShadowsEnabled": "(false)"}"#.into(),
+                },
+                GltfMeshExtras {
+                    value: r#"{"Collider": "Sphere(0.5)"}"#.into(),
+                },
+            ))
+            .id();
+        let mut scene = Scene::new(world);
+        reflect_scene_extras(&mut scene, context());
+
+        let entity = scene.world.entity(entity);
+        assert_eq!(entity.get::<ShadowsEnabled>(), Some(&ShadowsEnabled(false)));
+        assert_eq!(entity.get::<Collider>(), Some(&Collider::Sphere(0.5)));
+    }
+
+    #[test]
+    fn reports_unknown_keys() {
+        let (mut scene, entity) =
+            scene_with_extras(r#"{"Unknown": "()", "ShadowsEnabled": "(true)"}"#);
+        let errors = reflect_scene_extras(&mut scene, context());
+
+        assert_eq!(
+            errors,
+            vec![ExtrasReflectError::UnknownType {
+                entity,
+                name: None,
+                key: "Unknown".into(),
+                value: "()".into(),
+            }]
+        );
+        let entity = scene.world.entity(entity);
+        assert_eq!(entity.get::<ShadowsEnabled>(), Some(&ShadowsEnabled(true)));
+        assert!(entity.get::<Collider>().is_none());
+    }
+
+    #[test]
+    fn reports_malformed_ron_without_panicking() {
+        let mut world = scene_world();
+        let entity = world
+            .spawn((
+                Name::new("light"),
+                GltfExtras {
+                    value: r#"{"Collider": "Cube(1.0)"}"#.into(),
+                },
+            ))
+            .id();
+        let mut scene = Scene::new(world);
+        let errors = reflect_scene_extras(&mut scene, context());
+
+        let [ExtrasReflectError::Ron { .. }] = errors.as_slice() else {
+            panic!("expected a single ron error, got {errors:?}");
+        };
+        assert_eq!(errors[0].entity(), entity);
+        assert_eq!(errors[0].name(), Some(&Name::new("light")));
+        assert_eq!(errors[0].key(), Some("Collider"));
+        assert_eq!(errors[0].value(), "Cube(1.0)");
+        assert!(scene.world.entity(entity).get::<Collider>().is_none());
+    }
+
+    #[test]
+    fn reports_invalid_json() {
+        let (mut scene, entity) = scene_with_extras("not json");
+        let errors = reflect_scene_extras(&mut scene, context());
+
+        let [ExtrasReflectError::JsonParse { extras, .. }] = errors.as_slice() else {
+            panic!("expected a single json error, got {errors:?}");
+        };
+        assert_eq!(extras, "not json");
+        assert_eq!(errors[0].entity(), entity);
+        assert_eq!(errors[0].key(), None);
+    }
+}
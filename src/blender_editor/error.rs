@@ -0,0 +1,269 @@
+use std::{fmt, path::PathBuf};
+
+use bevy::prelude::*;
+
+// glTF extras 反射为组件时产生的错误
+#[derive(Event, Debug, Clone, PartialEq)]
+pub enum ExtrasReflectError {
+    // extras 不是 JSON 对象
+    JsonParse {
+        entity: Entity,
+        name: Option<Name>,
+        extras: String,
+        message: String,
+    },
+    // 键找不到对应的类型注册
+    UnknownType {
+        entity: Entity,
+        name: Option<Name>,
+        key: String,
+        value: String,
+    },
+    // 短类型名对应多个类型
+    AmbiguousType {
+        entity: Entity,
+        name: Option<Name>,
+        key: String,
+        value: String,
+        candidates: Vec<String>,
+    },
+    // 没有注册的预设
+    UnknownPreset {
+        entity: Entity,
+        name: Option<Name>,
+        key: String,
+        value: String,
+    },
+    // 非字符串的 JSON 值无法转换为对应的类型
+    NonStringValue {
+        entity: Entity,
+        name: Option<Name>,
+        key: String,
+        value: String,
+        message: String,
+    },
+    // RON 解析或反射反序列化失败
+    Ron {
+        entity: Entity,
+        name: Option<Name>,
+        key: String,
+        value: String,
+        message: String,
+    },
+    // 类型没有反射 Component
+    MissingReflectComponent {
+        entity: Entity,
+        name: Option<Name>,
+        key: String,
+        value: String,
+        type_path: String,
+    },
+}
+
+impl ExtrasReflectError {
+    // 出错的实体
+    pub fn entity(&self) -> Entity {
+        match self {
+            Self::JsonParse { entity, .. }
+            | Self::UnknownType { entity, .. }
+            | Self::AmbiguousType { entity, .. }
+            | Self::UnknownPreset { entity, .. }
+            | Self::NonStringValue { entity, .. }
+            | Self::Ron { entity, .. }
+            | Self::MissingReflectComponent { entity, .. } => *entity,
+        }
+    }
+
+    // 出错实体的名字
+    pub fn name(&self) -> Option<&Name> {
+        match self {
+            Self::JsonParse { name, .. }
+            | Self::UnknownType { name, .. }
+            | Self::AmbiguousType { name, .. }
+            | Self::UnknownPreset { name, .. }
+            | Self::NonStringValue { name, .. }
+            | Self::Ron { name, .. }
+            | Self::MissingReflectComponent { name, .. } => name.as_ref(),
+        }
+    }
+
+    // 出错的键，JSON 解析失败时没有键
+    pub fn key(&self) -> Option<&str> {
+        match self {
+            Self::JsonParse { .. } => None,
+            Self::UnknownType { key, .. }
+            | Self::AmbiguousType { key, .. }
+            | Self::UnknownPreset { key, .. }
+            | Self::NonStringValue { key, .. }
+            | Self::Ron { key, .. }
+            | Self::MissingReflectComponent { key, .. } => Some(key),
+        }
+    }
+
+    // 出错的原始值，JSON 解析失败时是整个 extras
+    pub fn value(&self) -> &str {
+        match self {
+            Self::JsonParse { extras, .. } => extras,
+            Self::UnknownType { value, .. }
+            | Self::AmbiguousType { value, .. }
+            | Self::UnknownPreset { value, .. }
+            | Self::NonStringValue { value, .. }
+            | Self::Ron { value, .. }
+            | Self::MissingReflectComponent { value, .. } => value,
+        }
+    }
+}
+
+impl fmt::Display for ExtrasReflectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let entity = self.entity();
+        let name = self.name().map(Name::as_str).unwrap_or_default();
+        let value = self.value();
+        match self {
+            Self::JsonParse { message, .. } => {
+                write!(
+                    f,
+                    "{entity} ({name}) extras json parse failed: {message}, extras: {value}"
+                )
+            }
+            Self::UnknownType { key, .. } => {
+                write!(
+                    f,
+                    "{entity} ({name}) extra `{key}` get type registration failed, value: {value}"
+                )
+            }
+            Self::AmbiguousType {
+                key, candidates, ..
+            } => {
+                write!(
+                    f,
+                    "{entity} ({name}) extra `{key}` is ambiguous, use one of [{}] or an alias, value: {value}",
+                    candidates.join(", ")
+                )
+            }
+            Self::UnknownPreset { key, .. } => {
+                write!(
+                    f,
+                    "{entity} ({name}) extra `{key}` preset is not registered, value: {value}"
+                )
+            }
+            Self::NonStringValue { key, message, .. } => {
+                write!(
+                    f,
+                    "{entity} ({name}) extra `{key}` json convert failed: {message}, value: {value}"
+                )
+            }
+            Self::Ron { key, message, .. } => {
+                write!(
+                    f,
+                    "{entity} ({name}) extra `{key}` ron parse failed: {message}, value: {value}"
+                )
+            }
+            Self::MissingReflectComponent { key, type_path, .. } => {
+                write!(f, "{entity} ({name}) extra `{key}` type `{type_path}` does not reflect Component, value: {value}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ExtrasReflectError {}
+
+// 将组件写回 glTF extras 时产生的错误
+#[derive(Event, Debug, Clone, PartialEq)]
+pub enum ExtrasWriteError {
+    // 读取或写入文件失败
+    Io {
+        path: PathBuf,
+        message: String,
+    },
+    // 文件不是有效的 .gltf 或 .glb
+    InvalidGltf {
+        path: PathBuf,
+        message: String,
+    },
+    // 组件序列化为 RON 失败
+    Serialize {
+        entity: Entity,
+        name: Name,
+        type_path: String,
+        message: String,
+    },
+    // glTF 中没有与实体同名的节点
+    MissingNode {
+        path: PathBuf,
+        name: Name,
+    },
+    // 实体不是 glTF 节点，例如以网格命名的图元实体，它的组件不会写回
+    NotNode {
+        entity: Entity,
+        name: Name,
+    },
+}
+
+impl fmt::Display for ExtrasWriteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io { path, message } => {
+                write!(f, "{} extras write failed: {message}", path.display())
+            }
+            Self::InvalidGltf { path, message } => {
+                write!(f, "{} is not a valid gltf: {message}", path.display())
+            }
+            Self::Serialize {
+                entity,
+                name,
+                type_path,
+                message,
+            } => {
+                write!(
+                    f,
+                    "{entity} ({name}) component `{type_path}` ron serialize failed: {message}"
+                )
+            }
+            Self::MissingNode { path, name } => {
+                write!(f, "{} has no node named `{name}`", path.display())
+            }
+            Self::NotNode { entity, name } => {
+                write!(
+                    f,
+                    "{entity} ({name}) is not a gltf node, components not written back"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ExtrasWriteError {}
+
+// 收集到的所有反射错误，方便工具和测试检查
+#[derive(Resource, Debug, Default, Deref, DerefMut)]
+pub struct ExtrasReflectErrors(pub Vec<ExtrasReflectError>);
+
+// 将反射错误事件收集到 ExtrasReflectErrors 资源
+pub(super) fn collect_extras_reflect_errors(
+    mut error_reader: EventReader<ExtrasReflectError>,
+    mut errors: ResMut<ExtrasReflectErrors>,
+) {
+    errors.extend(error_reader.read().cloned());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blender_editor::{test_utils::*, RequestedScenes};
+
+    #[test]
+    fn collects_reflect_error_events() {
+        let mut app = scenes_app();
+        let (scene, _) = scene_with_extras(r#"{"Unknown": "()"}"#);
+        let handle = app.world_mut().resource_mut::<Assets<Scene>>().add(scene);
+        app.world_mut()
+            .resource_mut::<RequestedScenes>()
+            .track(handle);
+        app.update();
+
+        let errors = app.world().resource::<ExtrasReflectErrors>();
+        let keys: Vec<_> = errors.iter().map(ExtrasReflectError::key).collect();
+        assert_eq!(keys, vec![Some("Unknown")]);
+    }
+}
@@ -0,0 +1,358 @@
+use std::any::TypeId;
+
+use bevy::{
+    reflect::{NamedField, TypeInfo, TypeRegistry, UnnamedField, VariantInfo},
+    scene::ron,
+};
+use serde_json::{Map, Value};
+
+// 将 Blender 自定义属性的 JSON 值，按照反射类型信息转换为 RON 字符串
+// 整数、浮点数、布尔值、数组和字典都会转换为对应的反射结构，嵌套的字符串在非字符串类型中视为 RON
+pub fn json_to_ron(
+    value: &Value,
+    type_info: &TypeInfo,
+    type_registry: &TypeRegistry,
+) -> Result<String, String> {
+    match type_info {
+        TypeInfo::Struct(info) => {
+            let fields: Vec<_> = info.iter().collect();
+            named_fields_to_ron(value, &fields, type_info.type_path(), type_registry)
+        }
+        TypeInfo::TupleStruct(info) => {
+            let fields: Vec<_> = info.iter().collect();
+            unnamed_fields_to_ron(value, &fields, type_info.type_path(), type_registry)
+        }
+        TypeInfo::Tuple(info) => {
+            let fields: Vec<_> = info.iter().collect();
+            unnamed_fields_to_ron(value, &fields, type_info.type_path(), type_registry)
+        }
+        TypeInfo::List(info) => items_to_ron(value, info.item_type_id(), type_registry),
+        TypeInfo::Array(info) => {
+            if let Value::Array(items) = value {
+                if items.len() != info.capacity() {
+                    return Err(format!(
+                        "expected {} items for `{}`, got {}",
+                        info.capacity(),
+                        type_info.type_path(),
+                        items.len()
+                    ));
+                }
+            }
+            items_to_ron(value, info.item_type_id(), type_registry)
+        }
+        TypeInfo::Map(info) => {
+            let Value::Object(map) = value else {
+                return string_as_ron(value, type_info);
+            };
+            let entries = map
+                .iter()
+                .map(|(key, value)| {
+                    let key = type_id_to_ron(
+                        &Value::String(key.clone()),
+                        info.key_type_id(),
+                        type_registry,
+                    )?;
+                    let value = type_id_to_ron(value, info.value_type_id(), type_registry)?;
+                    Ok(format!("{key}: {value}"))
+                })
+                .collect::<Result<Vec<_>, String>>()?;
+            Ok(format!("{{{}}}", entries.join(", ")))
+        }
+        TypeInfo::Enum(info) => {
+            if info.type_path().starts_with("core::option::Option") {
+                return match value {
+                    Value::Null => Ok("None".into()),
+                    Value::Object(map) if map.len() == 1 && map.contains_key("Some") => {
+                        variant_to_ron(&map["Some"], info.variant("Some"), type_registry)
+                    }
+                    value => variant_to_ron(value, info.variant("Some"), type_registry),
+                };
+            }
+            match value {
+                Value::String(name) if info.contains_variant(name) => {
+                    variant_to_ron(&Value::Null, info.variant(name), type_registry)
+                }
+                Value::Object(map) if map.len() == 1 => {
+                    let (name, value) = map.iter().next().unwrap();
+                    let Some(variant) = info.variant(name) else {
+                        return Err(format!(
+                            "`{name}` is not a variant of `{}`",
+                            info.type_path()
+                        ));
+                    };
+                    variant_to_ron(value, Some(variant), type_registry)
+                }
+                value => string_as_ron(value, type_info),
+            }
+        }
+        TypeInfo::Value(info) => primitive_to_ron(value, info.type_path()),
+    }
+}
+
+// 根据类型 ID 查找类型信息后转换
+fn type_id_to_ron(
+    value: &Value,
+    type_id: TypeId,
+    type_registry: &TypeRegistry,
+) -> Result<String, String> {
+    let Some(type_info) = type_registry.get_type_info(type_id) else {
+        return Err(format!("type {type_id:?} is not registered"));
+    };
+    json_to_ron(value, type_info, type_registry)
+}
+
+// 嵌套的字符串视为 RON，其他无法转换的值报错
+fn string_as_ron(value: &Value, type_info: &TypeInfo) -> Result<String, String> {
+    match value {
+        Value::String(ron) => Ok(ron.clone()),
+        value => Err(format!(
+            "can not convert {value} to `{}`",
+            type_info.type_path()
+        )),
+    }
+}
+
+// 转换具名字段，字典按字段名填充，数组按顺序填充，单个字段的结构体可直接使用标量
+fn named_fields_to_ron(
+    value: &Value,
+    fields: &[&NamedField],
+    type_path: &str,
+    type_registry: &TypeRegistry,
+) -> Result<String, String> {
+    let entries: Vec<(&NamedField, &Value)> = match value {
+        Value::Object(map) => {
+            check_unknown_fields(map, fields, type_path)?;
+            fields
+                .iter()
+                .filter_map(|&field| map.get(field.name()).map(|value| (field, value)))
+                .collect()
+        }
+        Value::Array(items) => {
+            if items.len() > fields.len() {
+                return Err(format!(
+                    "expected at most {} items for `{type_path}`, got {}",
+                    fields.len(),
+                    items.len()
+                ));
+            }
+            fields.iter().copied().zip(items).collect()
+        }
+        Value::String(ron) => return Ok(ron.clone()),
+        _ if fields.is_empty() => Vec::new(),
+        value if fields.len() == 1 => vec![(fields[0], value)],
+        value => return Err(format!("can not convert {value} to `{type_path}`")),
+    };
+    let entries = entries
+        .into_iter()
+        .map(|(field, value)| {
+            let value = type_id_to_ron(value, field.type_id(), type_registry)?;
+            Ok(format!("{}: {value}", field.name()))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok(format!("({})", entries.join(", ")))
+}
+
+// 转换匿名字段，数组按顺序填充，单个字段的元组结构体直接使用整个值
+fn unnamed_fields_to_ron(
+    value: &Value,
+    fields: &[&UnnamedField],
+    type_path: &str,
+    type_registry: &TypeRegistry,
+) -> Result<String, String> {
+    if let [field] = fields {
+        // 单个字段优先使用整个值，失败时再尝试只有一个元素的数组
+        let field_ron = match type_id_to_ron(value, field.type_id(), type_registry) {
+            Err(error) => match value {
+                Value::Array(items) if items.len() == 1 => {
+                    type_id_to_ron(&items[0], field.type_id(), type_registry).map_err(|_| error)
+                }
+                _ => Err(error),
+            },
+            field_ron => field_ron,
+        }?;
+        return Ok(format!("({field_ron})"));
+    }
+    let entries: Vec<(&UnnamedField, &Value)> = match value {
+        Value::Array(items) => {
+            if items.len() != fields.len() {
+                return Err(format!(
+                    "expected {} items for `{type_path}`, got {}",
+                    fields.len(),
+                    items.len()
+                ));
+            }
+            fields.iter().copied().zip(items).collect()
+        }
+        Value::String(ron) => return Ok(ron.clone()),
+        _ if fields.is_empty() => Vec::new(),
+        value => return Err(format!("can not convert {value} to `{type_path}`")),
+    };
+    let entries = entries
+        .into_iter()
+        .map(|(field, value)| type_id_to_ron(value, field.type_id(), type_registry))
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok(format!("({})", entries.join(", ")))
+}
+
+// 转换列表和数组的元素
+fn items_to_ron(
+    value: &Value,
+    item_type_id: TypeId,
+    type_registry: &TypeRegistry,
+) -> Result<String, String> {
+    let Value::Array(items) = value else {
+        return match value {
+            Value::String(ron) => Ok(ron.clone()),
+            value => Err(format!("can not convert {value} to a list")),
+        };
+    };
+    let items = items
+        .iter()
+        .map(|item| type_id_to_ron(item, item_type_id, type_registry))
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok(format!("[{}]", items.join(", ")))
+}
+
+// 转换枚举变体
+fn variant_to_ron(
+    value: &Value,
+    variant: Option<&VariantInfo>,
+    type_registry: &TypeRegistry,
+) -> Result<String, String> {
+    let Some(variant) = variant else {
+        return Err("enum variant not found".into());
+    };
+    match variant {
+        VariantInfo::Unit(info) => Ok(info.name().into()),
+        VariantInfo::Tuple(info) => {
+            let fields: Vec<_> = info.iter().collect();
+            let fields = unnamed_fields_to_ron(value, &fields, info.name(), type_registry)?;
+            Ok(format!("{}{fields}", info.name()))
+        }
+        VariantInfo::Struct(info) => {
+            let fields: Vec<_> = info.iter().collect();
+            let fields = named_fields_to_ron(value, &fields, info.name(), type_registry)?;
+            Ok(format!("{}{fields}", info.name()))
+        }
+    }
+}
+
+// 转换基本类型，数字和布尔值可以互相转换
+fn primitive_to_ron(value: &Value, type_path: &str) -> Result<String, String> {
+    let mismatch = || format!("can not convert {value} to `{type_path}`");
+    match type_path {
+        "bool" => match value {
+            Value::Bool(bool) => Ok(bool.to_string()),
+            Value::Number(number) => Ok((number.as_f64().ok_or_else(mismatch)? != 0.).to_string()),
+            Value::String(ron) => Ok(ron.clone()),
+            _ => Err(mismatch()),
+        },
+        "f32" | "f64" => match value {
+            Value::Number(number) => Ok(format!("{:?}", number.as_f64().ok_or_else(mismatch)?)),
+            Value::Bool(bool) => Ok(if *bool { "1.0" } else { "0.0" }.into()),
+            Value::String(ron) => Ok(ron.clone()),
+            _ => Err(mismatch()),
+        },
+        "i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "u8" | "u16" | "u32" | "u64" | "u128"
+        | "usize" => match value {
+            Value::Number(number) => {
+                if let Some(integer) = number.as_i64() {
+                    Ok(integer.to_string())
+                } else if let Some(integer) = number.as_u64() {
+                    Ok(integer.to_string())
+                } else {
+                    let float = number.as_f64().ok_or_else(mismatch)?;
+                    if float.fract() == 0. {
+                        Ok(format!("{float:.0}"))
+                    } else {
+                        Err(mismatch())
+                    }
+                }
+            }
+            Value::Bool(bool) => Ok(u8::from(*bool).to_string()),
+            Value::String(ron) => Ok(ron.clone()),
+            _ => Err(mismatch()),
+        },
+        "alloc::string::String" | "alloc::borrow::Cow<str>" | "char" => {
+            let string = match value {
+                Value::String(string) => string.clone(),
+                Value::Null => return Err(mismatch()),
+                value => value.to_string(),
+            };
+            if type_path == "char" {
+                ron::to_string(&string.chars().next().ok_or_else(mismatch)?)
+                    .map_err(|error| error.to_string())
+            } else {
+                ron::to_string(&string).map_err(|error| error.to_string())
+            }
+        }
+        _ => match value {
+            Value::String(ron) => Ok(ron.clone()),
+            _ => Err(mismatch()),
+        },
+    }
+}
+
+// 检查字典中是否有不存在的字段
+fn check_unknown_fields(
+    map: &Map<String, Value>,
+    fields: &[&NamedField],
+    type_path: &str,
+) -> Result<(), String> {
+    for key in map.keys() {
+        if !fields.iter().any(|field| field.name() == key) {
+            return Err(format!("`{type_path}` has no field `{key}`"));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use crate::blender_editor::{reflect_scene_extras, test_utils::*, ExtrasReflectError};
+
+    #[test]
+    fn converts_non_string_json_values() {
+        let (mut scene, entity) = scene_with_extras(
+            r#"{
+                "ShadowsEnabled": 1.0,
+                "Collider": {"Cuboid": [1, 2, 3]},
+                "Patrol": {"speed": 2, "points": [[0, 0, 0], {"x": 1.5}], "loops": null},
+                "Enemy": true
+            }"#,
+        );
+        let errors = reflect_scene_extras(&mut scene, context());
+
+        assert!(errors.is_empty(), "{errors:?}");
+        let entity = scene.world.entity(entity);
+        assert_eq!(entity.get::<ShadowsEnabled>(), Some(&ShadowsEnabled(true)));
+        assert_eq!(
+            entity.get::<Collider>(),
+            Some(&Collider::Cuboid(Vec3::new(1., 2., 3.)))
+        );
+        assert_eq!(
+            entity.get::<Patrol>(),
+            Some(&Patrol {
+                speed: 2.,
+                points: vec![Vec3::ZERO, Vec3::X * 1.5],
+                loops: None,
+            })
+        );
+        assert_eq!(entity.get::<Enemy>(), Some(&Enemy));
+    }
+
+    #[test]
+    fn reports_unconvertible_json_values() {
+        let (mut scene, entity) = scene_with_extras(r#"{"Collider": {"Capsule": 1.0}}"#);
+        let errors = reflect_scene_extras(&mut scene, context());
+
+        let [ExtrasReflectError::NonStringValue { message, .. }] = errors.as_slice() else {
+            panic!("expected a single conversion error, got {errors:?}");
+        };
+        assert!(message.contains("Capsule"));
+        assert_eq!(errors[0].value(), r#"{"Capsule":1.0}"#);
+        assert!(scene.world.entity(entity).get::<Collider>().is_none());
+    }
+}
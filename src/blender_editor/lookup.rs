@@ -0,0 +1,150 @@
+use std::any::TypeId;
+
+use bevy::{
+    prelude::*,
+    reflect::{TypeRegistration, TypeRegistry},
+    utils::HashMap,
+};
+
+// 用户定义的 extras 键别名，例如 "rb" -> RigidBodyMarker
+#[derive(Resource, Debug, Clone, Default)]
+pub struct ExtrasAliases(pub HashMap<String, TypeId>);
+
+impl ExtrasAliases {
+    // 添加别名，同名的别名会被覆盖
+    pub fn insert<T: Reflect>(&mut self, alias: impl Into<String>) {
+        self.0.insert(alias.into(), TypeId::of::<T>());
+    }
+}
+
+// 在 App 上注册 extras 键别名
+pub trait ExtrasAliasAppExt {
+    fn register_extras_alias<T: Reflect>(&mut self, alias: impl Into<String>) -> &mut Self;
+}
+
+impl ExtrasAliasAppExt for App {
+    fn register_extras_alias<T: Reflect>(&mut self, alias: impl Into<String>) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_insert_with(ExtrasAliases::default)
+            .insert::<T>(alias);
+        self
+    }
+}
+
+// 查找 extras 键对应的类型失败的原因
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExtrasLookupError {
+    // 没有任何类型与键匹配
+    Unknown,
+    // 短类型名对应多个类型，需要使用完整路径或别名
+    Ambiguous(Vec<String>),
+}
+
+// 依次通过别名、完整类型路径和短类型路径查找 extras 键对应的类型注册
+pub fn lookup_type_registration<'a>(
+    key: &str,
+    type_registry: &'a TypeRegistry,
+    aliases: &ExtrasAliases,
+) -> Result<&'a TypeRegistration, ExtrasLookupError> {
+    if let Some(&type_id) = aliases.0.get(key) {
+        return type_registry.get(type_id).ok_or(ExtrasLookupError::Unknown);
+    }
+    if let Some(type_registration) = type_registry.get_with_type_path(key) {
+        return Ok(type_registration);
+    }
+    if let Some(type_registration) = type_registry.get_with_short_type_path(key) {
+        return Ok(type_registration);
+    }
+    if type_registry.is_ambiguous(key) {
+        let mut candidates: Vec<String> = type_registry
+            .iter()
+            .map(|type_registration| type_registration.type_info().type_path_table())
+            .filter(|type_path_table| type_path_table.short_path() == key)
+            .map(|type_path_table| type_path_table.path().into())
+            .collect();
+        candidates.sort();
+        return Err(ExtrasLookupError::Ambiguous(candidates));
+    }
+    Err(ExtrasLookupError::Unknown)
+}
+
+// 类型在 extras 中的键，短类型名有歧义时使用完整路径
+pub fn extras_key<'a>(
+    type_registration: &'a TypeRegistration,
+    type_registry: &TypeRegistry,
+) -> &'a str {
+    let type_path_table = type_registration.type_info().type_path_table();
+    if type_registry.is_ambiguous(type_path_table.short_path()) {
+        type_path_table.path()
+    } else {
+        type_path_table.short_path()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use super::ExtrasAliases;
+    use crate::blender_editor::{
+        reflect_scene_extras, test_utils::*, ExtrasPresets, ExtrasReflectContext,
+        ExtrasReflectError,
+    };
+
+    mod physics {
+        use bevy::prelude::*;
+
+        #[derive(Component, Reflect, Debug, PartialEq)]
+        #[reflect(Component)]
+        pub enum Collider {
+            Ball(f32),
+        }
+
+        #[derive(Component, Reflect, Debug, PartialEq)]
+        #[reflect(Component)]
+        pub enum RigidBodyMarker {
+            Static,
+            Dynamic,
+        }
+    }
+
+    #[test]
+    fn looks_up_full_type_paths_and_aliases() {
+        let app_type_registry = type_registry();
+        app_type_registry.write().register::<physics::Collider>();
+        app_type_registry
+            .write()
+            .register::<physics::RigidBodyMarker>();
+        let mut aliases = ExtrasAliases::default();
+        aliases.insert::<physics::RigidBodyMarker>("rb");
+        let (mut scene, entity) = scene_with_extras(&format!(
+            r#"{{"{}": "Ball(0.5)", "rb": "Dynamic", "Collider": "Sphere(1.0)"}}"#,
+            physics::Collider::type_path()
+        ));
+        let errors = reflect_scene_extras(
+            &mut scene,
+            ExtrasReflectContext::new(app_type_registry, aliases, ExtrasPresets::default()),
+        );
+
+        let [ExtrasReflectError::AmbiguousType { candidates, .. }] = errors.as_slice() else {
+            panic!("expected a single ambiguity error, got {errors:?}");
+        };
+        // 候选类型按类型路径排序
+        let mut expected = vec![
+            Collider::type_path().to_string(),
+            physics::Collider::type_path().to_string(),
+        ];
+        expected.sort();
+        assert_eq!(candidates, &expected);
+        let entity = scene.world.entity(entity);
+        assert_eq!(
+            entity.get::<physics::Collider>(),
+            Some(&physics::Collider::Ball(0.5))
+        );
+        assert_eq!(
+            entity.get::<physics::RigidBodyMarker>(),
+            Some(&physics::RigidBodyMarker::Dynamic)
+        );
+        assert!(entity.get::<Collider>().is_none());
+    }
+}
@@ -0,0 +1,109 @@
+use std::sync::Arc;
+
+use bevy::{prelude::*, utils::HashMap};
+
+// 插入预设组件的函数
+pub type ExtrasPreset = Arc<dyn Fn(&mut EntityWorldMut) + Send + Sync>;
+
+// 在 Rust 中注册的组件预设，extras 中通过 "@preset": "enemy_basic" 使用
+// 预设会插入到场景的 World 中，所以其中的组件都需要注册反射类型，否则场景无法生成
+#[derive(Resource, Clone, Default)]
+pub struct ExtrasPresets(pub HashMap<String, ExtrasPreset>);
+
+impl ExtrasPresets {
+    // 添加预设，同名的预设会被覆盖
+    pub fn insert<B: Bundle + Clone>(&mut self, name: impl Into<String>, bundle: B) {
+        self.0.insert(
+            name.into(),
+            Arc::new(move |entity_mut: &mut EntityWorldMut| {
+                entity_mut.insert(bundle.clone());
+            }),
+        );
+    }
+}
+
+// 在 App 上注册组件预设
+pub trait ExtrasPresetAppExt {
+    fn register_extras_preset<B: Bundle + Clone>(
+        &mut self,
+        name: impl Into<String>,
+        bundle: B,
+    ) -> &mut Self;
+}
+
+impl ExtrasPresetAppExt for App {
+    fn register_extras_preset<B: Bundle + Clone>(
+        &mut self,
+        name: impl Into<String>,
+        bundle: B,
+    ) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_insert_with(ExtrasPresets::default)
+            .insert(name, bundle);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::gltf::GltfMaterialExtras;
+
+    use super::*;
+    use crate::blender_editor::{reflect_scene_extras, test_utils::*, ExtrasReflectError};
+
+    #[test]
+    fn applies_presets_tags_and_removals() {
+        let mut world = scene_world();
+        let entity = world
+            .spawn((
+                ShadowsEnabled(true),
+                GltfExtras {
+                    value: r##"{"@preset": "turret", "#Enemy": true, "-ShadowsEnabled": null}"##
+                        .into(),
+                },
+                GltfMaterialExtras {
+                    value: r#"{"Collider": "Sphere(2.0)"}"#.into(),
+                },
+            ))
+            .id();
+        let mut scene = Scene::new(world);
+        let mut context = context();
+        context.presets.insert(
+            "turret",
+            (
+                Collider::Sphere(1.),
+                Patrol {
+                    speed: 1.,
+                    points: Vec::new(),
+                    loops: None,
+                },
+            ),
+        );
+        let errors = reflect_scene_extras(&mut scene, context);
+
+        assert!(errors.is_empty());
+        let entity = scene.world.entity(entity);
+        assert_eq!(entity.get::<Enemy>(), Some(&Enemy));
+        assert_eq!(entity.get::<ShadowsEnabled>(), None);
+        // extras 中的组件会覆盖预设中的同类组件
+        assert_eq!(entity.get::<Collider>(), Some(&Collider::Sphere(2.)));
+        assert_eq!(entity.get::<Patrol>().map(|patrol| patrol.speed), Some(1.));
+    }
+
+    #[test]
+    fn reports_unknown_presets() {
+        let (mut scene, _) =
+            scene_with_extras(r#"{"@preset": ["missing"], "-UnknownComponent": null}"#);
+        let errors = reflect_scene_extras(&mut scene, context());
+
+        assert_eq!(errors.len(), 2);
+        assert!(errors.iter().any(|error| matches!(
+            error,
+            ExtrasReflectError::UnknownPreset { value, .. } if value == "missing"
+        )));
+        assert!(errors.iter().any(|error| matches!(
+            error,
+            ExtrasReflectError::UnknownType { key, .. } if key == "-UnknownComponent"
+        )));
+    }
+}
@@ -0,0 +1,423 @@
+use bevy::{
+    asset::{AssetLoadFailedEvent, AssetPath, LoadState},
+    ecs::system::SystemParam,
+    prelude::*,
+    utils::HashSet,
+};
+
+use super::{
+    collect_extras_reflect_errors, reflect_scene_extras_report, writer::write_requested_extras,
+    ExtrasAliases, ExtrasPresets, ExtrasReflectError, ExtrasReflectErrors, ExtrasReflectParam,
+    ExtrasReports, ExtrasValidationSettings, ExtrasWriteError, WriteBlenderExtras,
+};
+
+// 按需加载 Blender 场景，加载完成后将 extras 反射为组件，并在场景文件修改后重新反射
+// 每个场景的校验结果保存在 ExtrasReports 中，开启 ExtrasValidationSettings::strict 时没有通过校验的场景加载失败
+pub struct BlenderScenesPlugin;
+
+impl Plugin for BlenderScenesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<BlenderSceneProcessed>()
+            .add_event::<BlenderSceneFailed>()
+            .add_event::<ExtrasReflectError>()
+            .add_event::<WriteBlenderExtras>()
+            .add_event::<ExtrasWriteError>()
+            .init_resource::<RequestedScenes>()
+            .init_resource::<ExtrasReflectErrors>()
+            .init_resource::<ExtrasAliases>()
+            .init_resource::<ExtrasPresets>()
+            .init_resource::<ExtrasValidationSettings>()
+            .init_resource::<ExtrasReports>()
+            .init_resource::<ReflectedScenes>()
+            .add_systems(
+                Update,
+                (
+                    process_requested_scenes,
+                    reload_modified_scenes,
+                    collect_extras_reflect_errors,
+                )
+                    .chain()
+                    .in_set(BlenderScenesSet),
+            )
+            // 在 Update 之后写回，游戏中调整的值都已经生效
+            .add_systems(PostUpdate, write_requested_extras);
+    }
+}
+
+// 处理 Blender 场景的系统集，需要等待场景处理完成的系统可以排在它之后
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BlenderScenesSet;
+
+// 单个场景的组件反射完成
+#[derive(Event, Debug, Clone)]
+pub struct BlenderSceneProcessed {
+    pub handle: Handle<Scene>,
+}
+
+// 单个场景处理失败
+#[derive(Event, Debug, Clone)]
+pub struct BlenderSceneFailed {
+    pub handle: Handle<Scene>,
+    pub failure: BlenderSceneFailure,
+}
+
+// 场景处理失败的原因
+#[derive(Debug, Clone, PartialEq)]
+pub enum BlenderSceneFailure {
+    // 场景文件加载失败
+    Load(String),
+    // 严格模式下没有通过校验，详细结果保存在 ExtrasReports 中
+    Validation,
+}
+
+// 已请求的场景，保存强句柄以保持场景加载
+#[derive(Resource, Default)]
+pub struct RequestedScenes {
+    pending: Vec<Handle<Scene>>,
+    processed: Vec<Handle<Scene>>,
+    failed: Vec<(Handle<Scene>, BlenderSceneFailure)>,
+}
+
+impl RequestedScenes {
+    // 添加场景，已经添加过的场景会被忽略
+    pub fn track(&mut self, handle: Handle<Scene>) {
+        if !self.contains(handle.id()) {
+            self.pending.push(handle);
+        }
+    }
+
+    // 场景是否已经被请求
+    pub fn contains(&self, id: AssetId<Scene>) -> bool {
+        self.pending
+            .iter()
+            .chain(&self.processed)
+            .chain(self.failed.iter().map(|(handle, _)| handle))
+            .any(|handle| handle.id() == id)
+    }
+
+    // 场景是否已经反射完成
+    pub fn is_processed(&self, id: AssetId<Scene>) -> bool {
+        self.processed.iter().any(|handle| handle.id() == id)
+    }
+
+    // 场景加载失败或者严格模式下没有通过校验，场景文件修改后会重新处理
+    pub fn is_failed(&self, id: AssetId<Scene>) -> bool {
+        self.failure(id).is_some()
+    }
+
+    // 场景处理失败的原因
+    pub fn failure(&self, id: AssetId<Scene>) -> Option<&BlenderSceneFailure> {
+        self.failed
+            .iter()
+            .find(|(handle, _)| handle.id() == id)
+            .map(|(_, failure)| failure)
+    }
+
+    // 释放场景，之后不会再处理它的修改
+    pub fn release(&mut self, id: AssetId<Scene>) {
+        self.pending.retain(|handle| handle.id() != id);
+        self.processed.retain(|handle| handle.id() != id);
+        self.failed.retain(|(handle, _)| handle.id() != id);
+    }
+}
+
+// 请求加载 Blender 场景的系统参数
+#[derive(SystemParam)]
+pub struct BlenderScenes<'w> {
+    asset_server: Res<'w, AssetServer>,
+    requested: ResMut<'w, RequestedScenes>,
+}
+
+impl BlenderScenes<'_> {
+    // 请求加载场景，场景处理完成后会发送 BlenderSceneProcessed 事件
+    pub fn request<'a>(&mut self, path: impl Into<AssetPath<'a>>) -> Handle<Scene> {
+        let handle = self.asset_server.load(path);
+        self.requested.track(handle.clone());
+        handle
+    }
+
+    // 场景是否已经反射完成
+    pub fn is_processed(&self, handle: &Handle<Scene>) -> bool {
+        self.requested.is_processed(handle.id())
+    }
+
+    // 场景是否处理失败
+    pub fn is_failed(&self, handle: &Handle<Scene>) -> bool {
+        self.requested.is_failed(handle.id())
+    }
+
+    // 场景处理失败的原因
+    pub fn failure(&self, handle: &Handle<Scene>) -> Option<&BlenderSceneFailure> {
+        self.requested.failure(handle.id())
+    }
+
+    // 释放场景
+    pub fn release(&mut self, handle: &Handle<Scene>) {
+        self.requested.release(handle.id());
+    }
+}
+
+// 已经反射过组件的场景，反射时修改场景会产生 AssetEvent::Modified，需要跳过这些事件
+#[derive(Resource, Default)]
+pub(super) struct ReflectedScenes(pub(super) HashSet<AssetId<Scene>>);
+
+// 处理场景共用的资源和事件
+#[derive(SystemParam)]
+pub(super) struct SceneProcessing<'w> {
+    requested: ResMut<'w, RequestedScenes>,
+    scenes: ResMut<'w, Assets<Scene>>,
+    reflected_scenes: ResMut<'w, ReflectedScenes>,
+    reports: ResMut<'w, ExtrasReports>,
+    failed_writer: EventWriter<'w, BlenderSceneFailed>,
+    error_writer: EventWriter<'w, ExtrasReflectError>,
+    reflect_param: ExtrasReflectParam<'w>,
+}
+
+impl SceneProcessing<'_> {
+    // 反射场景并记录校验结果，场景还没有加载时返回 None，否则返回是否通过校验
+    fn reflect(&mut self, id: AssetId<Scene>) -> Option<bool> {
+        let scene = self.scenes.get_mut(id)?;
+        self.reflected_scenes.0.insert(id);
+        let report = reflect_scene_extras_report(scene, self.reflect_param.context());
+        for error in &report.errors {
+            error!("{error}");
+            self.error_writer.send(error.clone());
+        }
+        for duplicate in &report.duplicates {
+            warn!("{duplicate}");
+        }
+        let clean = report.is_clean();
+        if !clean && self.is_strict() {
+            error!("blender scene {id} failed extras validation: {report}");
+        }
+        self.reports.insert(id, report);
+        Some(clean)
+    }
+
+    fn is_strict(&self) -> bool {
+        self.reflect_param.settings.strict
+    }
+
+    // 记录处理失败的场景并发送事件
+    fn fail(&mut self, handle: Handle<Scene>, failure: BlenderSceneFailure) {
+        self.failed_writer.send(BlenderSceneFailed {
+            handle: handle.clone(),
+            failure: failure.clone(),
+        });
+        self.requested.failed.push((handle, failure));
+    }
+}
+
+// 反射已经加载完成的场景
+pub(super) fn process_requested_scenes(
+    mut processing: SceneProcessing,
+    asset_server: Res<AssetServer>,
+    mut load_failed_reader: EventReader<AssetLoadFailedEvent<Scene>>,
+    mut processed_writer: EventWriter<BlenderSceneProcessed>,
+) {
+    // 本帧加载失败的场景，请求前就已经失败的场景通过加载状态判断
+    let load_errors: Vec<_> = load_failed_reader
+        .read()
+        .map(|failed| (failed.id, failed.error.to_string()))
+        .collect();
+    if processing.requested.pending.is_empty() {
+        return;
+    }
+    let pending = std::mem::take(&mut processing.requested.pending);
+    for handle in pending {
+        let Some(clean) = processing.reflect(handle.id()) else {
+            let load_error = load_errors
+                .iter()
+                .find(|(id, _)| *id == handle.id())
+                .map(|(_, error)| error.clone())
+                .or_else(|| match asset_server.get_load_state(handle.id()) {
+                    Some(LoadState::Failed(error)) => Some(error.to_string()),
+                    _ => None,
+                });
+            if let Some(error) = load_error {
+                error!("blender scene {:?} load failed: {error}", handle.path());
+                processing.fail(handle, BlenderSceneFailure::Load(error));
+            } else {
+                processing.requested.pending.push(handle);
+            }
+            continue;
+        };
+        if !clean && processing.is_strict() {
+            processing.fail(handle, BlenderSceneFailure::Validation);
+            continue;
+        }
+        processed_writer.send(BlenderSceneProcessed {
+            handle: handle.clone(),
+        });
+        processing.requested.processed.push(handle);
+    }
+}
+
+// 场景文件重新导出后，重新反射组件，并重新生成已有的场景实例
+pub(super) fn reload_modified_scenes(
+    mut asset_events: EventReader<AssetEvent<Scene>>,
+    mut processing: SceneProcessing,
+    mut scene_instances: Query<&mut Handle<Scene>>,
+) {
+    for asset_event in asset_events.read() {
+        let &AssetEvent::Modified { id } = asset_event else {
+            continue;
+        };
+        // 由反射组件产生的修改事件
+        if processing.reflected_scenes.0.remove(&id) {
+            continue;
+        }
+        let requested = &mut processing.requested;
+        // 没有通过校验的场景重新导出后，重新处理
+        if requested.is_failed(id) {
+            let index = requested
+                .failed
+                .iter()
+                .position(|(handle, _)| handle.id() == id);
+            if let Some(index) = index {
+                let (handle, _) = requested.failed.remove(index);
+                requested.pending.push(handle);
+            }
+            continue;
+        }
+        let Some(index) = requested
+            .processed
+            .iter()
+            .position(|handle| handle.id() == id)
+        else {
+            continue;
+        };
+        info!("scene {id} modified, reflect components from extras again");
+        let Some(clean) = processing.reflect(id) else {
+            continue;
+        };
+        // 严格模式下重新导出的场景没有通过校验，不再重新生成场景实例
+        if !clean && processing.is_strict() {
+            let handle = processing.requested.processed.remove(index);
+            processing.fail(handle, BlenderSceneFailure::Validation);
+            continue;
+        }
+        for mut scene_handle in &mut scene_instances {
+            if scene_handle.id() == id {
+                scene_handle.set_changed();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::asset::AssetLoadError;
+
+    use super::*;
+    use crate::blender_editor::test_utils::*;
+
+    #[test]
+    fn processes_requested_scenes_once_loaded() {
+        let mut app = scenes_app();
+        let handle = app.world().resource::<Assets<Scene>>().reserve_handle();
+        app.world_mut()
+            .resource_mut::<RequestedScenes>()
+            .track(handle.clone());
+        app.update();
+        assert!(!app
+            .world()
+            .resource::<RequestedScenes>()
+            .is_processed(handle.id()));
+
+        // 模拟场景加载完成
+        let (scene, entity) = scene_with_extras(r#"{"ShadowsEnabled": "(true)"}"#);
+        app.world_mut()
+            .resource_mut::<Assets<Scene>>()
+            .insert(&handle, scene);
+        app.update();
+
+        assert!(app
+            .world()
+            .resource::<RequestedScenes>()
+            .is_processed(handle.id()));
+        let processed: Vec<_> = app
+            .world_mut()
+            .resource_mut::<Events<BlenderSceneProcessed>>()
+            .drain()
+            .map(|processed| processed.handle)
+            .collect();
+        assert_eq!(processed, vec![handle.clone()]);
+        let scenes = app.world().resource::<Assets<Scene>>();
+        assert_eq!(
+            scenes
+                .get(&handle)
+                .unwrap()
+                .world
+                .entity(entity)
+                .get::<ShadowsEnabled>(),
+            Some(&ShadowsEnabled(true))
+        );
+    }
+
+    #[test]
+    fn records_scenes_that_fail_to_load() {
+        let mut app = scenes_app();
+        let handle = app.world().resource::<Assets<Scene>>().reserve_handle();
+        app.world_mut()
+            .resource_mut::<RequestedScenes>()
+            .track(handle.clone());
+        app.update();
+        assert!(!app
+            .world()
+            .resource::<RequestedScenes>()
+            .is_failed(handle.id()));
+
+        // 模拟资产服务器报告加载失败
+        app.world_mut().send_event(AssetLoadFailedEvent {
+            id: handle.id(),
+            path: "missing.glb".into(),
+            error: AssetLoadError::AssetMetaReadError,
+        });
+        app.update();
+
+        let requested = app.world().resource::<RequestedScenes>();
+        assert!(matches!(
+            requested.failure(handle.id()),
+            Some(BlenderSceneFailure::Load(_))
+        ));
+        assert!(!requested.is_processed(handle.id()));
+        let failed: Vec<_> = app
+            .world_mut()
+            .resource_mut::<Events<BlenderSceneFailed>>()
+            .drain()
+            .map(|failed| failed.handle)
+            .collect();
+        assert_eq!(failed, vec![handle]);
+    }
+
+    #[test]
+    fn reflects_modified_scenes_again() {
+        let mut app = scenes_app();
+        let (scene, _) = scene_with_extras(r#"{"ShadowsEnabled": "(true)"}"#);
+        let handle = app.world_mut().resource_mut::<Assets<Scene>>().add(scene);
+        app.world_mut()
+            .resource_mut::<RequestedScenes>()
+            .track(handle.clone());
+        for _ in 0..3 {
+            app.update();
+        }
+
+        // 模拟重新导出场景
+        let (scene, entity) = scene_with_extras(r#"{"ShadowsEnabled": "(false)"}"#);
+        app.world_mut()
+            .resource_mut::<Assets<Scene>>()
+            .insert(&handle, scene);
+        for _ in 0..3 {
+            app.update();
+        }
+
+        let scenes = app.world().resource::<Assets<Scene>>();
+        let scene = scenes.get(&handle).unwrap();
+        assert_eq!(
+            scene.world.entity(entity).get::<ShadowsEnabled>(),
+            Some(&ShadowsEnabled(false))
+        );
+        assert!(app.world().resource::<ReflectedScenes>().0.is_empty());
+    }
+}
@@ -0,0 +1,452 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use bevy::{
+    prelude::*,
+    reflect::{
+        serde::TypedReflectSerializer, NamedField, TypeInfo, TypeRegistry, UnnamedField,
+        VariantInfo,
+    },
+};
+use serde_json::{json, Map, Value};
+
+use super::{extras_key, ExtrasAliases, ExtrasPresets, PRESET_KEY, REMOVE_PREFIX, TAG_PREFIX};
+
+// 导出组件 JSON Schema 的命令行参数，例如 `cargo run --bin blender_as_bevy_editor -- --export-schema components.json`
+pub const EXPORT_SCHEMA_ARG: &str = "--export-schema";
+
+// 没有指定路径时导出的文件
+pub const DEFAULT_SCHEMA_PATH: &str = "components.schema.json";
+
+// 启动后将所有反射了 Component 的类型导出为 JSON Schema，然后退出
+// 与 MinimalPlugins 一起使用即可在没有窗口的情况下运行，Blender 插件可以根据 Schema 生成下拉框和类型化的输入框
+pub struct ExportSchemaPlugin {
+    pub path: PathBuf,
+}
+
+impl ExportSchemaPlugin {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    // 命令行参数中有 `--export-schema [path]` 时创建插件
+    pub fn from_args() -> Option<Self> {
+        let mut args = std::env::args().skip_while(|arg| arg != EXPORT_SCHEMA_ARG);
+        args.next()?;
+        Some(Self::new(
+            args.next().unwrap_or_else(|| DEFAULT_SCHEMA_PATH.into()),
+        ))
+    }
+}
+
+impl Plugin for ExportSchemaPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, export_components_schema(self.path.clone()));
+    }
+}
+
+// 导出 Schema 并退出
+fn export_components_schema(
+    path: PathBuf,
+) -> impl FnMut(
+    Res<AppTypeRegistry>,
+    Option<Res<ExtrasAliases>>,
+    Option<Res<ExtrasPresets>>,
+    EventWriter<AppExit>,
+) {
+    move |app_type_registry, aliases, presets, mut exit_writer| {
+        let schema = components_schema(
+            &app_type_registry.read(),
+            &aliases.as_deref().cloned().unwrap_or_default(),
+            &presets.as_deref().cloned().unwrap_or_default(),
+        );
+        match write_components_schema(&path, &schema) {
+            Ok(()) => {
+                info!("components schema exported to {}", path.display());
+                exit_writer.send(AppExit::Success);
+            }
+            Err(error) => {
+                error!(
+                    "export components schema to {} failed: {error}",
+                    path.display()
+                );
+                exit_writer.send(AppExit::error());
+            }
+        }
+    }
+}
+
+// 将 Schema 写入文件
+pub fn write_components_schema(path: &Path, schema: &Value) -> io::Result<()> {
+    let json = serde_json::to_string_pretty(schema)?;
+    fs::write(path, json)
+}
+
+// 生成描述 extras 的 JSON Schema
+// properties 中是可以在 extras 中使用的键（短类型名，有歧义时使用完整路径，以及别名），类型定义都在 $defs 中
+pub fn components_schema(
+    type_registry: &TypeRegistry,
+    aliases: &ExtrasAliases,
+    presets: &ExtrasPresets,
+) -> Value {
+    let mut definitions = Map::new();
+    let mut properties = Map::new();
+    let mut components: Vec<_> = type_registry
+        .iter()
+        .filter(|type_registration| type_registration.data::<ReflectComponent>().is_some())
+        .collect();
+    components.sort_by_key(|type_registration| type_registration.type_info().type_path());
+    for type_registration in components {
+        properties.insert(
+            extras_key(type_registration, type_registry).into(),
+            component_property(
+                type_registration.type_info(),
+                type_registry,
+                &mut definitions,
+            ),
+        );
+    }
+
+    let mut aliases: Vec<_> = aliases.0.iter().collect();
+    aliases.sort_by_key(|(alias, _)| alias.as_str());
+    for (alias, &type_id) in aliases {
+        let Some(type_registration) = type_registry.get(type_id) else {
+            continue;
+        };
+        if type_registration.data::<ReflectComponent>().is_none() {
+            continue;
+        }
+        properties.insert(
+            alias.clone(),
+            component_property(
+                type_registration.type_info(),
+                type_registry,
+                &mut definitions,
+            ),
+        );
+    }
+
+    let mut preset_names: Vec<_> = presets.0.keys().cloned().collect();
+    preset_names.sort();
+    properties.insert(
+        PRESET_KEY.into(),
+        json!({
+            "description": "insert bundles registered in Rust",
+            "anyOf": [
+                { "enum": preset_names },
+                { "type": "array", "items": { "enum": preset_names } },
+            ],
+        }),
+    );
+
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "Blender extras components",
+        "type": "object",
+        "properties": properties,
+        "patternProperties": {
+            format!("^{REMOVE_PREFIX}"): { "description": "remove the component, the value is ignored" },
+            format!("^{TAG_PREFIX}"): { "description": "insert the component with its default value, the value is ignored" },
+        },
+        "additionalProperties": false,
+        "$defs": definitions,
+    })
+}
+
+// extras 中的组件值，可以是 JSON 值，也可以是 RON 字符串
+fn component_property(
+    type_info: &TypeInfo,
+    type_registry: &TypeRegistry,
+    definitions: &mut Map<String, Value>,
+) -> Value {
+    add_definition(type_info, type_registry, definitions);
+    json!({
+        "anyOf": [
+            definition_ref(type_info.type_path()),
+            { "type": "string", "description": "RON" },
+        ],
+    })
+}
+
+// 添加类型定义，已经存在的类型会被跳过
+fn add_definition(
+    type_info: &TypeInfo,
+    type_registry: &TypeRegistry,
+    definitions: &mut Map<String, Value>,
+) {
+    let type_path_table = type_info.type_path_table();
+    if definitions.contains_key(type_path_table.path()) {
+        return;
+    }
+    // 先占位，避免递归类型无限展开
+    definitions.insert(type_path_table.path().into(), Value::Null);
+    let mut schema = type_schema(type_info, type_registry, definitions);
+    if let Value::Object(schema) = &mut schema {
+        schema.insert("title".into(), type_path_table.short_path().into());
+        schema.insert("x-type-path".into(), type_path_table.path().into());
+        if let Some(type_registration) = type_registry.get(type_info.type_id()) {
+            schema.insert(
+                "x-component".into(),
+                type_registration
+                    .data::<ReflectComponent>()
+                    .is_some()
+                    .into(),
+            );
+            if let Some(default) =
+                type_registration
+                    .data::<ReflectDefault>()
+                    .and_then(|reflect_default| {
+                        let default = reflect_default.default();
+                        serde_json::to_value(TypedReflectSerializer::new(&*default, type_registry))
+                            .ok()
+                    })
+            {
+                schema.insert("default".into(), default);
+            }
+        }
+    }
+    definitions.insert(type_path_table.path().into(), schema);
+}
+
+// 引用 $defs 中的类型定义
+fn definition_ref(type_path: &str) -> Value {
+    let pointer = type_path.replace('~', "~0").replace('/', "~1");
+    json!({ "$ref": format!("#/$defs/{pointer}") })
+}
+
+// 根据类型 ID 生成字段的 Schema，基本类型直接内联，其他类型引用 $defs
+fn type_id_schema(
+    type_id: std::any::TypeId,
+    type_registry: &TypeRegistry,
+    definitions: &mut Map<String, Value>,
+) -> Value {
+    let Some(type_info) = type_registry.get_type_info(type_id) else {
+        return json!({ "description": "unregistered type" });
+    };
+    if let TypeInfo::Value(info) = type_info {
+        return primitive_schema(info.type_path());
+    }
+    add_definition(type_info, type_registry, definitions);
+    definition_ref(type_info.type_path())
+}
+
+// 与 json_to_ron 接受的 JSON 形式保持一致
+fn type_schema(
+    type_info: &TypeInfo,
+    type_registry: &TypeRegistry,
+    definitions: &mut Map<String, Value>,
+) -> Value {
+    match type_info {
+        TypeInfo::Struct(info) => {
+            let fields: Vec<_> = info.iter().collect();
+            with_kind(
+                "struct",
+                named_fields_schema(&fields, type_registry, definitions),
+            )
+        }
+        TypeInfo::TupleStruct(info) => {
+            let fields: Vec<_> = info.iter().collect();
+            with_kind(
+                "tuple_struct",
+                unnamed_fields_schema(&fields, type_registry, definitions),
+            )
+        }
+        TypeInfo::Tuple(info) => {
+            let fields: Vec<_> = info.iter().collect();
+            with_kind(
+                "tuple",
+                unnamed_fields_schema(&fields, type_registry, definitions),
+            )
+        }
+        TypeInfo::List(info) => json!({
+            "x-kind": "list",
+            "type": "array",
+            "items": type_id_schema(info.item_type_id(), type_registry, definitions),
+        }),
+        TypeInfo::Array(info) => json!({
+            "x-kind": "array",
+            "type": "array",
+            "items": type_id_schema(info.item_type_id(), type_registry, definitions),
+            "minItems": info.capacity(),
+            "maxItems": info.capacity(),
+        }),
+        TypeInfo::Map(info) => json!({
+            "x-kind": "map",
+            "type": "object",
+            "additionalProperties": type_id_schema(info.value_type_id(), type_registry, definitions),
+        }),
+        TypeInfo::Enum(info) => {
+            if info.type_path().starts_with("core::option::Option") {
+                let some = info
+                    .variant("Some")
+                    .map(|variant| variant_value_schema(variant, type_registry, definitions))
+                    .unwrap_or_default();
+                return json!({
+                    "x-kind": "option",
+                    "anyOf": [{ "type": "null" }, some],
+                });
+            }
+            let variants: Vec<_> = info
+                .iter()
+                .map(|variant| match variant {
+                    VariantInfo::Unit(_) => json!({ "const": variant.name() }),
+                    _ => json!({
+                        "type": "object",
+                        "properties": {
+                            variant.name(): variant_value_schema(variant, type_registry, definitions),
+                        },
+                        "required": [variant.name()],
+                        "additionalProperties": false,
+                    }),
+                })
+                .collect();
+            json!({
+                "x-kind": "enum",
+                "x-variants": info.variant_names(),
+                "oneOf": variants,
+            })
+        }
+        TypeInfo::Value(info) => with_kind("value", primitive_schema(info.type_path())),
+    }
+}
+
+// 给 Schema 加上反射类型的种类
+fn with_kind(kind: &str, mut schema: Value) -> Value {
+    if let Value::Object(schema) = &mut schema {
+        schema.insert("x-kind".into(), kind.into());
+    }
+    schema
+}
+
+// 枚举变体的值
+fn variant_value_schema(
+    variant: &VariantInfo,
+    type_registry: &TypeRegistry,
+    definitions: &mut Map<String, Value>,
+) -> Value {
+    match variant {
+        VariantInfo::Unit(_) => json!({ "type": "null" }),
+        VariantInfo::Tuple(info) => {
+            let fields: Vec<_> = info.iter().collect();
+            unnamed_fields_schema(&fields, type_registry, definitions)
+        }
+        VariantInfo::Struct(info) => {
+            let fields: Vec<_> = info.iter().collect();
+            named_fields_schema(&fields, type_registry, definitions)
+        }
+    }
+}
+
+// 具名字段可以是字典，也可以是按字段顺序的数组
+fn named_fields_schema(
+    fields: &[&NamedField],
+    type_registry: &TypeRegistry,
+    definitions: &mut Map<String, Value>,
+) -> Value {
+    let mut properties = Map::new();
+    let mut prefix_items = Vec::new();
+    for field in fields {
+        let field_schema = type_id_schema(field.type_id(), type_registry, definitions);
+        properties.insert(field.name().into(), field_schema.clone());
+        prefix_items.push(field_schema);
+    }
+    json!({
+        "type": ["object", "array"],
+        "properties": properties,
+        "additionalProperties": false,
+        "prefixItems": prefix_items,
+        "maxItems": fields.len(),
+    })
+}
+
+// 匿名字段是按顺序的数组，只有一个字段时直接使用字段的值
+fn unnamed_fields_schema(
+    fields: &[&UnnamedField],
+    type_registry: &TypeRegistry,
+    definitions: &mut Map<String, Value>,
+) -> Value {
+    if let [field] = fields {
+        return json!({
+            "allOf": [type_id_schema(field.type_id(), type_registry, definitions)],
+        });
+    }
+    let prefix_items: Vec<_> = fields
+        .iter()
+        .map(|field| type_id_schema(field.type_id(), type_registry, definitions))
+        .collect();
+    json!({
+        "type": "array",
+        "prefixItems": prefix_items,
+        "minItems": fields.len(),
+        "maxItems": fields.len(),
+    })
+}
+
+// 基本类型，其他没有结构信息的类型使用 RON 字符串
+fn primitive_schema(type_path: &str) -> Value {
+    match type_path {
+        "bool" => json!({ "type": "boolean" }),
+        "f32" | "f64" => json!({ "type": "number" }),
+        "i8" | "i16" | "i32" | "i64" | "i128" | "isize" => json!({ "type": "integer" }),
+        "u8" | "u16" | "u32" | "u64" | "u128" | "usize" => {
+            json!({ "type": "integer", "minimum": 0 })
+        }
+        "alloc::string::String" | "alloc::borrow::Cow<str>" => json!({ "type": "string" }),
+        "char" => json!({ "type": "string", "minLength": 1, "maxLength": 1 }),
+        type_path => json!({
+            "type": "string",
+            "description": format!("RON value of `{type_path}`"),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blender_editor::test_utils::*;
+
+    #[test]
+    fn exports_components_schema() {
+        let app_type_registry = type_registry();
+        let mut aliases = ExtrasAliases::default();
+        aliases.insert::<Collider>("col");
+        let mut presets = ExtrasPresets::default();
+        presets.insert("enemy", Enemy);
+        let schema = components_schema(&app_type_registry.read(), &aliases, &presets);
+
+        let properties = &schema["properties"];
+        for key in [
+            "ShadowsEnabled",
+            "Collider",
+            "Patrol",
+            "Enemy",
+            "col",
+            PRESET_KEY,
+        ] {
+            assert!(properties.get(key).is_some(), "missing property {key}");
+        }
+        assert_eq!(properties[PRESET_KEY]["anyOf"][0]["enum"], json!(["enemy"]));
+
+        let definitions = &schema["$defs"];
+        let collider = &definitions[std::any::type_name::<Collider>()];
+        assert_eq!(collider["x-kind"], "enum");
+        assert_eq!(collider["x-variants"], json!(["Cuboid", "Sphere"]));
+        assert_eq!(
+            collider["oneOf"][1]["properties"]["Sphere"]["allOf"][0]["type"],
+            "number"
+        );
+        let patrol = &definitions[std::any::type_name::<Patrol>()];
+        assert_eq!(patrol["properties"]["speed"]["type"], "number");
+        assert_eq!(
+            patrol["properties"]["points"]["$ref"],
+            "#/$defs/alloc::vec::Vec<glam::Vec3>"
+        );
+        assert_eq!(
+            patrol["properties"]["loops"]["$ref"],
+            "#/$defs/core::option::Option<u32>"
+        );
+        assert_eq!(patrol["x-component"], true);
+    }
+}
@@ -0,0 +1,93 @@
+// 测试共用的组件、类型注册表和场景
+use bevy::tasks::{ComputeTaskPool, TaskPool};
+
+use super::{
+    scenes::{process_requested_scenes, reload_modified_scenes, ReflectedScenes},
+    *,
+};
+
+#[derive(Component, Reflect, Debug, PartialEq)]
+#[reflect(Component)]
+pub struct ShadowsEnabled(pub bool);
+
+#[derive(Component, Reflect, Debug, Clone, PartialEq)]
+#[reflect(Component)]
+pub enum Collider {
+    Cuboid(Vec3),
+    Sphere(f32),
+}
+
+#[derive(Component, Reflect, Debug, Clone, PartialEq)]
+#[reflect(Component)]
+pub struct Patrol {
+    pub speed: f32,
+    pub points: Vec<Vec3>,
+    pub loops: Option<u32>,
+}
+
+#[derive(Component, Reflect, Debug, Clone, PartialEq)]
+#[reflect(Component)]
+pub struct Enemy;
+
+pub fn context() -> ExtrasReflectContext {
+    ExtrasReflectContext::new(
+        type_registry(),
+        ExtrasAliases::default(),
+        ExtrasPresets::default(),
+    )
+}
+
+pub fn type_registry() -> AppTypeRegistry {
+    let app_type_registry = AppTypeRegistry::default();
+    {
+        let mut type_registry = app_type_registry.write();
+        type_registry.register::<ShadowsEnabled>();
+        type_registry.register::<Collider>();
+        type_registry.register::<Patrol>();
+        type_registry.register::<Enemy>();
+    }
+    app_type_registry
+}
+
+pub fn scene_world() -> World {
+    ComputeTaskPool::get_or_init(TaskPool::default);
+    World::new()
+}
+
+pub fn scene_with_extras(extras: &str) -> (Scene, Entity) {
+    let mut world = scene_world();
+    let entity = world
+        .spawn(GltfExtras {
+            value: extras.into(),
+        })
+        .id();
+    (Scene::new(world), entity)
+}
+
+// 只运行场景处理系统的应用
+pub fn scenes_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+        .init_asset::<Scene>()
+        .insert_resource(type_registry())
+        .init_resource::<RequestedScenes>()
+        .init_resource::<ExtrasAliases>()
+        .init_resource::<ExtrasPresets>()
+        .init_resource::<ExtrasValidationSettings>()
+        .init_resource::<ExtrasReports>()
+        .init_resource::<ReflectedScenes>()
+        .init_resource::<ExtrasReflectErrors>()
+        .add_event::<BlenderSceneProcessed>()
+        .add_event::<BlenderSceneFailed>()
+        .add_event::<ExtrasReflectError>()
+        .add_systems(
+            Update,
+            (
+                process_requested_scenes,
+                reload_modified_scenes,
+                collect_extras_reflect_errors,
+            )
+                .chain(),
+        );
+    app
+}
@@ -0,0 +1,370 @@
+use std::{any::TypeId, fmt, fs, io, path::Path};
+
+use bevy::{
+    gltf::{GltfMaterialExtras, GltfMeshExtras, GltfSceneExtras},
+    prelude::*,
+    tasks::{ComputeTaskPool, TaskPool},
+    utils::{HashMap, HashSet},
+};
+use serde_json::Value;
+
+use super::{
+    writer::{split_gltf, GLB_MAGIC},
+    ExtrasReflectError,
+};
+
+// 校验 extras 的设置
+#[derive(Resource, Debug, Clone, Default)]
+pub struct ExtrasValidationSettings {
+    // 场景没有通过校验时加载失败，不会发送 BlenderSceneProcessed
+    pub strict: bool,
+    // 忽略的键，例如其他 Blender 插件写入的自定义属性
+    pub ignored_keys: HashSet<String>,
+}
+
+impl ExtrasValidationSettings {
+    // 开启严格模式
+    pub fn strict(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+
+    // 添加忽略的键
+    pub fn ignore_key(mut self, key: impl Into<String>) -> Self {
+        self.ignored_keys.insert(key.into());
+        self
+    }
+}
+
+// extras 所在的 glTF 对象
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExtrasSource {
+    Scene,
+    Node,
+    Mesh,
+    Material,
+}
+
+impl fmt::Display for ExtrasSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Scene => write!(f, "scene"),
+            Self::Node => write!(f, "node"),
+            Self::Mesh => write!(f, "mesh"),
+            Self::Material => write!(f, "material"),
+        }
+    }
+}
+
+// 同一个实体从多个 extras 中得到了同一个组件，后插入的会覆盖先插入的
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuplicateComponent {
+    pub entity: Entity,
+    pub name: Option<Name>,
+    pub type_path: String,
+    pub sources: Vec<ExtrasSource>,
+}
+
+impl fmt::Display for DuplicateComponent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = self.name.as_ref().map(Name::as_str).unwrap_or_default();
+        let sources: Vec<_> = self.sources.iter().map(ToString::to_string).collect();
+        write!(
+            f,
+            "{} ({name}) component `{}` comes from multiple extras: [{}]",
+            self.entity,
+            self.type_path,
+            sources.join(", ")
+        )
+    }
+}
+
+// 一个场景的 extras 校验结果
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExtrasReport {
+    // 所有反射错误，包括未知的键
+    pub errors: Vec<ExtrasReflectError>,
+    pub duplicates: Vec<DuplicateComponent>,
+    // 有 extras 但是没有得到任何组件的实体
+    pub empty_entities: Vec<(Entity, Option<Name>)>,
+}
+
+impl ExtrasReport {
+    // 没有任何问题
+    pub fn is_clean(&self) -> bool {
+        self.errors.is_empty() && self.duplicates.is_empty() && self.empty_entities.is_empty()
+    }
+
+    // 找不到对应类型的键
+    pub fn unknown_keys(&self) -> impl Iterator<Item = &ExtrasReflectError> {
+        self.errors
+            .iter()
+            .filter(|error| matches!(error, ExtrasReflectError::UnknownType { .. }))
+    }
+
+    // 合并其他报告
+    pub fn extend(&mut self, other: Self) {
+        self.errors.extend(other.errors);
+        self.duplicates.extend(other.duplicates);
+        self.empty_entities.extend(other.empty_entities);
+    }
+
+    // 记录一个实体的校验结果，inserted 是插入的组件和它来自的 extras，empty 表示没有插入任何组件
+    pub(super) fn add_entity(
+        &mut self,
+        entity: Entity,
+        name: Option<&Name>,
+        inserted: &[(TypeId, &str, ExtrasSource)],
+        errors: Vec<ExtrasReflectError>,
+        empty: bool,
+    ) {
+        if empty {
+            self.empty_entities.push((entity, name.cloned()));
+        }
+        self.errors.extend(errors);
+        let mut sources: Vec<(TypeId, &str, Vec<ExtrasSource>)> = Vec::new();
+        for &(type_id, type_path, source) in inserted {
+            match sources.iter_mut().find(|(id, ..)| *id == type_id) {
+                Some((.., component_sources)) => component_sources.push(source),
+                None => sources.push((type_id, type_path, vec![source])),
+            }
+        }
+        self.duplicates.extend(
+            sources
+                .into_iter()
+                .filter(|(.., sources)| sources.len() > 1)
+                .map(|(_, type_path, sources)| DuplicateComponent {
+                    entity,
+                    name: name.cloned(),
+                    type_path: type_path.into(),
+                    sources,
+                }),
+        );
+    }
+}
+
+impl fmt::Display for ExtrasReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} errors, {} duplicate components, {} entities without components",
+            self.errors.len(),
+            self.duplicates.len(),
+            self.empty_entities.len()
+        )
+    }
+}
+
+// 每个已处理场景的校验结果，场景重新反射后会被替换
+#[derive(Resource, Debug, Default, Deref, DerefMut)]
+pub struct ExtrasReports(pub HashMap<AssetId<Scene>, ExtrasReport>);
+
+// 不经过 AssetServer 直接读取 .gltf/.glb 中的 extras，生成只包含 extras 的场景，用于无界面的校验
+// 每个节点生成一个实体，节点网格的 extras 和材质的 extras 放在它的子实体上，与 glTF 加载器的结构一致
+pub fn load_gltf_extras(path: &Path) -> io::Result<Scene> {
+    let bytes = fs::read(path)?;
+    let (gltf, _) = split_gltf(&bytes, bytes.starts_with(GLB_MAGIC))
+        .map_err(|message| io::Error::new(io::ErrorKind::InvalidData, message))?;
+    let extras = |value: &Value| value.get("extras").map(Value::to_string);
+    let objects = |key: &str| {
+        gltf.get(key)
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default()
+    };
+    let meshes = objects("meshes");
+    let materials = objects("materials");
+
+    ComputeTaskPool::get_or_init(TaskPool::default);
+    let mut world = World::new();
+    for scene in objects("scenes") {
+        if let Some(value) = extras(&scene) {
+            world.spawn(GltfSceneExtras { value });
+        }
+    }
+    for (index, node) in objects("nodes").iter().enumerate() {
+        let name = node
+            .get("name")
+            .and_then(Value::as_str)
+            .map_or_else(|| format!("GltfNode{index}"), Into::into);
+        let mut node_entity = world.spawn(Name::new(name));
+        if let Some(value) = extras(node) {
+            node_entity.insert(GltfExtras { value });
+        }
+        let Some(mesh) = node
+            .get("mesh")
+            .and_then(Value::as_u64)
+            .and_then(|mesh| meshes.get(mesh as usize))
+        else {
+            continue;
+        };
+        let primitives = mesh
+            .get("primitives")
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+        node_entity.with_children(|parent| {
+            for primitive in primitives {
+                let mut primitive_entity = parent.spawn_empty();
+                if let Some(value) = extras(mesh) {
+                    primitive_entity.insert(GltfMeshExtras { value });
+                }
+                if let Some(value) = primitive
+                    .get("material")
+                    .and_then(Value::as_u64)
+                    .and_then(|material| materials.get(material as usize))
+                    .and_then(extras)
+                {
+                    primitive_entity.insert(GltfMaterialExtras { value });
+                }
+            }
+        });
+    }
+    Ok(Scene::new(world))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blender_editor::{
+        reflect_scene_extras_report, test_utils::*, BlenderSceneProcessed, RequestedScenes,
+    };
+
+    #[test]
+    fn reports_duplicate_and_empty_entities() {
+        let mut world = scene_world();
+        let duplicate_entity = world
+            .spawn((
+                Name::new("enemy"),
+                GltfExtras {
+                    value: r#"{"Collider": "Sphere(1.0)", "booleans": {}}"#.into(),
+                },
+                GltfMeshExtras {
+                    value: r#"{"Collider": "Sphere(2.0)"}"#.into(),
+                },
+            ))
+            .id();
+        let empty_entity = world
+            .spawn(GltfExtras {
+                value: r#"{"Colider": "Sphere(1.0)"}"#.into(),
+            })
+            .id();
+        let removal_entity = world
+            .spawn(GltfExtras {
+                value: r#"{"-ShadowsEnabled": null}"#.into(),
+            })
+            .id();
+        world.spawn(GltfExtras {
+            value: r#"{"booleans": {}}"#.into(),
+        });
+        let mut scene = Scene::new(world);
+        let context =
+            context().with_settings(ExtrasValidationSettings::default().ignore_key("booleans"));
+        let report = reflect_scene_extras_report(&mut scene, context);
+
+        assert!(!report.is_clean());
+        let unknown_keys: Vec<_> = report.unknown_keys().map(|error| error.key()).collect();
+        assert_eq!(unknown_keys, vec![Some("Colider")]);
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(
+            report.duplicates,
+            vec![DuplicateComponent {
+                entity: duplicate_entity,
+                name: Some(Name::new("enemy")),
+                type_path: std::any::type_name::<Collider>().into(),
+                sources: vec![ExtrasSource::Node, ExtrasSource::Mesh],
+            }]
+        );
+        // 只有删除组件的实体也没有插入任何组件
+        let mut empty_entities = report.empty_entities.clone();
+        empty_entities.sort_by_key(|(entity, _)| *entity);
+        assert_eq!(
+            empty_entities,
+            vec![(empty_entity, None), (removal_entity, None)]
+        );
+    }
+
+    #[test]
+    fn strict_mode_fails_scenes_until_reexported() {
+        let mut app = scenes_app();
+        app.insert_resource(ExtrasValidationSettings::default().strict());
+        let (scene, _) = scene_with_extras(r#"{"Unknown": "()"}"#);
+        let handle = app.world_mut().resource_mut::<Assets<Scene>>().add(scene);
+        app.world_mut()
+            .resource_mut::<RequestedScenes>()
+            .track(handle.clone());
+        for _ in 0..3 {
+            app.update();
+        }
+
+        let requested = app.world().resource::<RequestedScenes>();
+        assert!(requested.is_failed(handle.id()));
+        assert!(!requested.is_processed(handle.id()));
+        assert!(app
+            .world()
+            .resource::<Events<BlenderSceneProcessed>>()
+            .is_empty());
+        assert!(!app.world().resource::<ExtrasReports>()[&handle.id()].is_clean());
+
+        // 修正后重新导出场景
+        let (scene, _) = scene_with_extras(r#"{"ShadowsEnabled": "(true)"}"#);
+        app.world_mut()
+            .resource_mut::<Assets<Scene>>()
+            .insert(&handle, scene);
+        for _ in 0..3 {
+            app.update();
+        }
+
+        let requested = app.world().resource::<RequestedScenes>();
+        assert!(requested.is_processed(handle.id()));
+        assert!(!requested.is_failed(handle.id()));
+        assert!(app.world().resource::<ExtrasReports>()[&handle.id()].is_clean());
+    }
+
+    #[test]
+    fn strict_mode_fails_broken_reexports() {
+        let mut app = scenes_app();
+        app.insert_resource(ExtrasValidationSettings::default().strict());
+        let (scene, _) = scene_with_extras(r#"{"ShadowsEnabled": "(true)"}"#);
+        let handle = app.world_mut().resource_mut::<Assets<Scene>>().add(scene);
+        app.world_mut()
+            .resource_mut::<RequestedScenes>()
+            .track(handle.clone());
+        let instance = app.world_mut().spawn(handle.clone()).id();
+        for _ in 0..3 {
+            app.update();
+        }
+        assert!(app
+            .world()
+            .resource::<RequestedScenes>()
+            .is_processed(handle.id()));
+        let last_changed = app
+            .world()
+            .entity(instance)
+            .get_ref::<Handle<Scene>>()
+            .unwrap()
+            .last_changed();
+
+        // 重新导出的场景没有通过校验
+        let (scene, _) = scene_with_extras(r#"{"Unknown": "()"}"#);
+        app.world_mut()
+            .resource_mut::<Assets<Scene>>()
+            .insert(&handle, scene);
+        for _ in 0..3 {
+            app.update();
+        }
+
+        let requested = app.world().resource::<RequestedScenes>();
+        assert!(requested.is_failed(handle.id()));
+        assert!(!requested.is_processed(handle.id()));
+        assert!(!app.world().resource::<ExtrasReports>()[&handle.id()].is_clean());
+        assert_eq!(
+            app.world()
+                .entity(instance)
+                .get_ref::<Handle<Scene>>()
+                .unwrap()
+                .last_changed(),
+            last_changed
+        );
+    }
+}
@@ -0,0 +1,519 @@
+use std::{
+    any::TypeId,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use bevy::{
+    ecs::event::ManualEventReader,
+    gltf::{GltfMaterialExtras, GltfMeshExtras},
+    prelude::*,
+    reflect::{serde::TypedReflectSerializer, TypeRegistry},
+    scene::ron,
+};
+use serde_json::{Map, Value};
+
+use super::{
+    extras_key, lookup_type_registration, ExtrasAliases, ExtrasWriteError, PRESET_KEY,
+    REMOVE_PREFIX, TAG_PREFIX,
+};
+
+// 将场景实例中选定的组件写回 .gltf/.glb 文件的节点 extras，值使用与 reflect_component 相同的 RON 格式
+// 写入文件后，开启 file_watcher 时场景会被重新加载并重新反射
+#[derive(Event, Debug, Clone)]
+pub struct WriteBlenderExtras {
+    // 场景实例的根实体，也就是 SceneBundle 所在的实体
+    pub scene_root: Entity,
+    // 场景的源文件路径
+    pub path: PathBuf,
+    pub components: Vec<TypeId>,
+}
+
+impl WriteBlenderExtras {
+    pub fn new(scene_root: Entity, path: impl Into<PathBuf>) -> Self {
+        Self {
+            scene_root,
+            path: path.into(),
+            components: Vec::new(),
+        }
+    }
+
+    // 添加需要写回的组件类型
+    pub fn with<T: Component>(mut self) -> Self {
+        self.components.push(TypeId::of::<T>());
+        self
+    }
+}
+
+// 一个节点需要写回的组件，值是 RON 字符串
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeExtras {
+    pub name: Name,
+    pub components: Vec<(TypeId, String)>,
+}
+
+// 处理写回 extras 的请求
+pub(super) fn write_requested_extras(
+    world: &mut World,
+    mut request_reader: Local<ManualEventReader<WriteBlenderExtras>>,
+) {
+    let requests: Vec<_> = request_reader
+        .read(world.resource::<Events<WriteBlenderExtras>>())
+        .cloned()
+        .collect();
+    for request in requests {
+        let errors = write_scene_extras(world, &request);
+        if errors.is_empty() {
+            info!("extras written back to {}", request.path.display());
+        }
+        for error in errors {
+            error!("{error}");
+            world.send_event(error);
+        }
+    }
+}
+
+// 将场景实例中的组件写回 glTF 文件，返回写回失败的错误
+pub fn write_scene_extras(world: &World, request: &WriteBlenderExtras) -> Vec<ExtrasWriteError> {
+    let type_registry = world.resource::<AppTypeRegistry>().read();
+    let aliases = world
+        .get_resource::<ExtrasAliases>()
+        .cloned()
+        .unwrap_or_default();
+    let (nodes, mut errors) = collect_node_extras(
+        world,
+        request.scene_root,
+        &request.components,
+        &type_registry,
+    );
+    errors.extend(write_gltf_extras(
+        &request.path,
+        &nodes,
+        &type_registry,
+        &aliases,
+    ));
+    errors
+}
+
+// 收集场景实例中有名字的实体上选定组件的 RON 值，没有选定组件的实体会被跳过
+// 网格图元实体以网格命名，可能与节点重名，它们的组件不写回而是报告错误
+pub fn collect_node_extras(
+    world: &World,
+    scene_root: Entity,
+    components: &[TypeId],
+    type_registry: &TypeRegistry,
+) -> (Vec<NodeExtras>, Vec<ExtrasWriteError>) {
+    let mut nodes = Vec::new();
+    let mut errors = Vec::new();
+    let mut entities = vec![scene_root];
+    while let Some(entity) = entities.pop() {
+        let Some(entity_ref) = world.get_entity(entity) else {
+            continue;
+        };
+        if let Some(children) = entity_ref.get::<Children>() {
+            entities.extend(children.iter().rev());
+        }
+        let Some(name) = entity_ref.get::<Name>() else {
+            continue;
+        };
+        let mut node = NodeExtras {
+            name: name.clone(),
+            components: Vec::new(),
+        };
+        for &type_id in components {
+            let Some(type_registration) = type_registry.get(type_id) else {
+                continue;
+            };
+            let Some(component) = type_registration
+                .data::<ReflectComponent>()
+                .and_then(|reflect_component| reflect_component.reflect(entity_ref))
+            else {
+                continue;
+            };
+            match ron::to_string(&TypedReflectSerializer::new(component, type_registry)) {
+                Ok(ron_value) => node.components.push((type_id, ron_value)),
+                Err(error) => errors.push(ExtrasWriteError::Serialize {
+                    entity,
+                    name: name.clone(),
+                    type_path: type_registration.type_info().type_path().into(),
+                    message: error.to_string(),
+                }),
+            }
+        }
+        if node.components.is_empty() {
+            continue;
+        }
+        if is_mesh_primitive(entity_ref) {
+            errors.push(ExtrasWriteError::NotNode {
+                entity,
+                name: node.name,
+            });
+            continue;
+        }
+        nodes.push(node);
+    }
+    (nodes, errors)
+}
+
+// glTF 加载器为网格的每个图元生成一个子实体，节点实体本身不带网格
+fn is_mesh_primitive(entity_ref: EntityRef) -> bool {
+    entity_ref.contains::<Handle<Mesh>>()
+        || entity_ref.contains::<GltfMeshExtras>()
+        || entity_ref.contains::<GltfMaterialExtras>()
+}
+
+// .glb 文件头
+pub(super) const GLB_MAGIC: &[u8] = b"glTF";
+// .glb 的 JSON 块类型
+const GLB_JSON_CHUNK: u32 = 0x4E4F_534A;
+
+// 将组件写入 glTF 文件中同名节点的 extras，其他键保持不变
+pub fn write_gltf_extras(
+    path: &Path,
+    nodes: &[NodeExtras],
+    type_registry: &TypeRegistry,
+    aliases: &ExtrasAliases,
+) -> Vec<ExtrasWriteError> {
+    let io_error = |error: std::io::Error| ExtrasWriteError::Io {
+        path: path.into(),
+        message: error.to_string(),
+    };
+    let invalid_gltf = |message: String| ExtrasWriteError::InvalidGltf {
+        path: path.into(),
+        message,
+    };
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(error) => return vec![io_error(error)],
+    };
+    let is_glb = bytes.starts_with(GLB_MAGIC);
+    let (mut gltf, binary_chunks) = match split_gltf(&bytes, is_glb) {
+        Ok(gltf) => gltf,
+        Err(message) => return vec![invalid_gltf(message)],
+    };
+    let Some(gltf_nodes) = gltf.get_mut("nodes").and_then(Value::as_array_mut) else {
+        return vec![invalid_gltf("missing nodes".into())];
+    };
+    if let Some(index) = gltf_nodes.iter().position(|node| !node.is_object()) {
+        return vec![invalid_gltf(format!("node {index} is not an object"))];
+    }
+
+    let mut errors = Vec::new();
+    for node in nodes {
+        let mut found = false;
+        for gltf_node in gltf_nodes.iter_mut().filter_map(Value::as_object_mut) {
+            if gltf_node.get("name").and_then(Value::as_str) != Some(node.name.as_str()) {
+                continue;
+            }
+            found = true;
+            let extras = gltf_node
+                .entry("extras")
+                .or_insert_with(|| Value::Object(Map::new()));
+            if extras.is_null() {
+                *extras = Value::Object(Map::new());
+            }
+            let Value::Object(extras) = extras else {
+                errors.push(invalid_gltf(format!(
+                    "node `{}` extras is not an object",
+                    node.name
+                )));
+                continue;
+            };
+            merge_node_extras(extras, node, type_registry, aliases);
+        }
+        if !found {
+            errors.push(ExtrasWriteError::MissingNode {
+                path: path.into(),
+                name: node.name.clone(),
+            });
+        }
+    }
+
+    let bytes = if is_glb {
+        serde_json::to_vec(&gltf).map(|json| join_glb(json, binary_chunks))
+    } else {
+        serde_json::to_vec_pretty(&gltf)
+    };
+    match bytes {
+        Ok(bytes) => {
+            if let Err(error) = fs::write(path, bytes) {
+                errors.push(io_error(error));
+            }
+        }
+        Err(error) => errors.push(invalid_gltf(error.to_string())),
+    }
+    errors
+}
+
+// 已有的键对应同一个类型时（别名、完整路径或短类型名）直接覆盖它的值，否则使用 extras_key 添加新键
+// 同一类型的标记键会被删除，避免插入默认值
+fn merge_node_extras(
+    extras: &mut Map<String, Value>,
+    node: &NodeExtras,
+    type_registry: &TypeRegistry,
+    aliases: &ExtrasAliases,
+) {
+    let key_type_id = |key: &str| {
+        lookup_type_registration(key, type_registry, aliases)
+            .ok()
+            .map(|type_registration| type_registration.type_id())
+    };
+    for (type_id, ron_value) in &node.components {
+        extras.retain(|key, _| {
+            key.strip_prefix(TAG_PREFIX)
+                .is_none_or(|type_key| key_type_id(type_key) != Some(*type_id))
+        });
+        let key = extras
+            .keys()
+            .find(|&key| {
+                key != PRESET_KEY
+                    && !key.starts_with(REMOVE_PREFIX)
+                    && key_type_id(key) == Some(*type_id)
+            })
+            .cloned()
+            .or_else(|| {
+                type_registry
+                    .get(*type_id)
+                    .map(|type_registration| extras_key(type_registration, type_registry).into())
+            });
+        if let Some(key) = key {
+            extras.insert(key, Value::String(ron_value.clone()));
+        }
+    }
+}
+
+// 解析 glTF 的 JSON，.glb 同时返回 JSON 块之后的所有块
+pub(super) fn split_gltf(bytes: &[u8], is_glb: bool) -> Result<(Value, &[u8]), String> {
+    if !is_glb {
+        let gltf = serde_json::from_slice(bytes).map_err(|error| error.to_string())?;
+        return Ok((gltf, &[]));
+    }
+    let read_u32 = |offset: usize| {
+        bytes
+            .get(offset..offset + 4)
+            .map(|word| u32::from_le_bytes([word[0], word[1], word[2], word[3]]))
+            .ok_or_else(|| "unexpected end of glb".to_string())
+    };
+    let json_length = read_u32(12)? as usize;
+    if read_u32(16)? != GLB_JSON_CHUNK {
+        return Err("first glb chunk is not json".into());
+    }
+    let json = bytes
+        .get(20..20 + json_length)
+        .ok_or_else(|| "unexpected end of glb".to_string())?;
+    let gltf = serde_json::from_slice(json).map_err(|error| error.to_string())?;
+    Ok((gltf, &bytes[20 + json_length..]))
+}
+
+// 组装 .glb，JSON 块使用空格补齐到 4 字节对齐
+fn join_glb(mut json: Vec<u8>, binary_chunks: &[u8]) -> Vec<u8> {
+    json.resize(json.len().next_multiple_of(4), b' ');
+    let length = 12 + 8 + json.len() + binary_chunks.len();
+    let mut bytes = Vec::with_capacity(length);
+    bytes.extend_from_slice(GLB_MAGIC);
+    bytes.extend_from_slice(&2u32.to_le_bytes());
+    bytes.extend_from_slice(&(length as u32).to_le_bytes());
+    bytes.extend_from_slice(&(json.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(&GLB_JSON_CHUNK.to_le_bytes());
+    bytes.extend_from_slice(&json);
+    bytes.extend_from_slice(binary_chunks);
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::blender_editor::{reflect_scene_extras, test_utils::*};
+
+    fn temp_gltf_path(file_name: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("bevy_games_{}", std::process::id()));
+        std::fs::create_dir_all(&path).unwrap();
+        path.join(file_name)
+    }
+
+    fn scene_instance() -> (World, Entity) {
+        let mut world = scene_world();
+        world.insert_resource(type_registry());
+        let scene_root = world
+            .spawn_empty()
+            .with_children(|parent| {
+                parent.spawn((
+                    Name::new("barbette"),
+                    Collider::Sphere(2.),
+                    Patrol {
+                        speed: 1.5,
+                        points: vec![Vec3::X],
+                        loops: None,
+                    },
+                ));
+                parent.spawn((Name::new("light"), ShadowsEnabled(true)));
+            })
+            .id();
+        (world, scene_root)
+    }
+
+    #[test]
+    fn writes_components_back_into_gltf_extras() {
+        let path = temp_gltf_path("scene.gltf");
+        let gltf = json!({
+            "nodes": [
+                { "name": "barbette", "extras": { "Collider": "Sphere(1.0)", "#Patrol": 1, "prop": 3 } },
+                { "name": "light" },
+            ],
+        });
+        std::fs::write(&path, gltf.to_string()).unwrap();
+        let (world, scene_root) = scene_instance();
+        let request = WriteBlenderExtras::new(scene_root, &path)
+            .with::<Collider>()
+            .with::<Patrol>();
+        let errors = write_scene_extras(&world, &request);
+
+        assert!(errors.is_empty(), "{errors:?}");
+        let gltf: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        let extras = &gltf["nodes"][0]["extras"];
+        assert_eq!(extras["prop"], 3);
+        assert!(extras.get("#Patrol").is_none());
+        assert_eq!(gltf["nodes"][1].get("extras"), None);
+
+        // 写回的值可以被重新反射为相同的组件
+        let mut extras = extras.clone();
+        extras.as_object_mut().unwrap().remove("prop");
+        let (mut scene, entity) = scene_with_extras(&extras.to_string());
+        let errors = reflect_scene_extras(&mut scene, context());
+        assert!(errors.is_empty(), "{errors:?}");
+        let entity = scene.world.entity(entity);
+        assert_eq!(entity.get::<Collider>(), Some(&Collider::Sphere(2.)));
+        assert_eq!(
+            entity.get::<Patrol>(),
+            Some(&Patrol {
+                speed: 1.5,
+                points: vec![Vec3::X],
+                loops: None,
+            })
+        );
+    }
+
+    #[test]
+    fn reports_nodes_that_are_not_objects() {
+        let path = temp_gltf_path("invalid_nodes.gltf");
+        std::fs::write(
+            &path,
+            json!({ "nodes": [{ "name": "light" }, 1] }).to_string(),
+        )
+        .unwrap();
+        let nodes = [NodeExtras {
+            name: Name::new("light"),
+            components: vec![(TypeId::of::<ShadowsEnabled>(), "(true)".into())],
+        }];
+        let errors = write_gltf_extras(
+            &path,
+            &nodes,
+            &type_registry().read(),
+            &ExtrasAliases::default(),
+        );
+
+        assert_eq!(
+            errors,
+            vec![ExtrasWriteError::InvalidGltf {
+                path,
+                message: "node 1 is not an object".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn reports_mesh_primitives_instead_of_writing_them() {
+        let mut world = scene_world();
+        let mut primitive = Entity::PLACEHOLDER;
+        let scene_root = world
+            .spawn(Name::new("barbette"))
+            .insert(Collider::Sphere(2.))
+            .with_children(|parent| {
+                // 图元实体以网格命名，与节点同名
+                primitive = parent
+                    .spawn((
+                        Name::new("barbette"),
+                        Handle::<Mesh>::default(),
+                        GltfMeshExtras {
+                            value: r#"{"Collider": "Sphere(5.0)"}"#.into(),
+                        },
+                        Collider::Sphere(5.),
+                    ))
+                    .id();
+            })
+            .id();
+        let type_registry = type_registry();
+        let (nodes, errors) = collect_node_extras(
+            &world,
+            scene_root,
+            &[TypeId::of::<Collider>()],
+            &type_registry.read(),
+        );
+
+        assert_eq!(
+            nodes,
+            vec![NodeExtras {
+                name: Name::new("barbette"),
+                components: vec![(TypeId::of::<Collider>(), "Sphere(2.0)".into())],
+            }]
+        );
+        assert_eq!(
+            errors,
+            vec![ExtrasWriteError::NotNode {
+                entity: primitive,
+                name: Name::new("barbette"),
+            }]
+        );
+    }
+
+    #[test]
+    fn writes_glb_extras_and_keeps_binary_chunk() {
+        let path = temp_gltf_path("scene.glb");
+        let json = json!({ "nodes": [{ "name": "light" }] }).to_string();
+        let binary_chunk = [4u8, 0, 0, 0, 0x42, 0x49, 0x4E, 0x00, 1, 2, 3, 4];
+        let mut aliases = ExtrasAliases::default();
+        aliases.insert::<ShadowsEnabled>("shadows");
+        let nodes = [NodeExtras {
+            name: Name::new("light"),
+            components: vec![(std::any::TypeId::of::<ShadowsEnabled>(), "(true)".into())],
+        }];
+        std::fs::write(&path, join_glb(json.into_bytes(), &binary_chunk)).unwrap();
+        let errors = write_gltf_extras(&path, &nodes, &type_registry().read(), &aliases);
+
+        assert!(errors.is_empty(), "{errors:?}");
+        let bytes = std::fs::read(&path).unwrap();
+        assert_eq!(&bytes[..4], GLB_MAGIC);
+        assert_eq!(
+            u32::from_le_bytes(bytes[8..12].try_into().unwrap()) as usize,
+            bytes.len()
+        );
+        assert!(bytes.ends_with(&binary_chunk));
+        let json_length = u32::from_le_bytes(bytes[12..16].try_into().unwrap()) as usize;
+        assert_eq!(json_length % 4, 0);
+        let gltf: serde_json::Value = serde_json::from_slice(&bytes[20..20 + json_length]).unwrap();
+        assert_eq!(
+            gltf["nodes"][0]["extras"],
+            json!({ "ShadowsEnabled": "(true)" })
+        );
+
+        let errors = write_gltf_extras(
+            &path,
+            &[NodeExtras {
+                name: Name::new("missing"),
+                components: Vec::new(),
+            }],
+            &type_registry().read(),
+            &aliases,
+        );
+        assert_eq!(
+            errors,
+            vec![ExtrasWriteError::MissingNode {
+                path,
+                name: Name::new("missing"),
+            }]
+        );
+    }
+}
@@ -0,0 +1,3 @@
+#![allow(clippy::type_complexity)]
+
+pub mod blender_editor;
@@ -1,51 +1,61 @@
-mod blender_editor;
-
 use bevy::{
     asset::AssetPath,
-    input::common_conditions::input_pressed,
+    input::common_conditions::{input_just_pressed, input_pressed},
     math::bounding::{Aabb3d, BoundingSphere, IntersectsVolume},
     prelude::*,
     scene::SceneInstanceReady,
 };
-use blender_editor::{BlenderEditorPlugin, SceneHandles};
+use bevy_games::blender_editor::{
+    BlenderEditorPlugin, ExportSchemaPlugin, SceneHandles, WriteBlenderExtras,
+};
 
 fn main() -> AppExit {
-    App::new()
-        .add_plugins((
-            DefaultPlugins,
-            BlenderEditorPlugin::new(
-                vec![AssetPath::from(
-                    "blender_as_bevy_editor/barbette.glb#Scene0",
-                )],
-                GameState::Start,
-            ),
-        ))
-        .add_plugins(bevy_inspector_egui::quick::WorldInspectorPlugin::default())
-        .register_type::<ShadowsEnabled>()
+    let mut app = App::new();
+    register_types(&mut app);
+    // 无界面导出组件的 JSON Schema 后退出
+    if let Some(export_schema_plugin) = ExportSchemaPlugin::from_args() {
+        return app.add_plugins((MinimalPlugins, export_schema_plugin)).run();
+    }
+    app.add_plugins((
+        DefaultPlugins,
+        BlenderEditorPlugin::new(
+            vec![AssetPath::from(
+                "blender_as_bevy_editor/barbette.glb#Scene0",
+            )],
+            GameState::Start,
+        ),
+    ))
+    .add_plugins(bevy_inspector_egui::quick::WorldInspectorPlugin::default())
+    .init_state::<GameState>()
+    .add_systems(OnEnter(GameState::Start), spawn_scene)
+    .add_systems(
+        Update,
+        (
+            (spawn_barbette_timer, shadows_enabled, add_red_material)
+                .run_if(on_event::<SceneInstanceReady>()),
+            (
+                move_barbette,
+                tick_timer,
+                move_cannonballs,
+                despawn_cannonballs,
+                attack_enemies,
+                move_enemies,
+                emit_cannonballs.run_if(input_pressed(KeyCode::Space)),
+                write_extras.run_if(input_just_pressed(KeyCode::F5)),
+            )
+                .run_if(in_state(GameState::Start)),
+        ),
+    )
+    .run()
+}
+
+// 注册 extras 中使用的组件
+fn register_types(app: &mut App) {
+    app.register_type::<ShadowsEnabled>()
         .register_type::<Barbette>()
         .register_type::<Enemy>()
         .register_type::<Collider>()
-        .register_type::<Cannonball>()
-        .init_state::<GameState>()
-        .add_systems(OnEnter(GameState::Start), spawn_scene)
-        .add_systems(
-            Update,
-            (
-                (spawn_barbette_timer, shadows_enabled, add_red_material)
-                    .run_if(on_event::<SceneInstanceReady>()),
-                (
-                    move_barbette,
-                    tick_timer,
-                    move_cannonballs,
-                    despawn_cannonballs,
-                    attack_enemies,
-                    move_enemies,
-                    emit_cannonballs.run_if(input_pressed(KeyCode::Space)),
-                )
-                    .run_if(in_state(GameState::Start)),
-            ),
-        )
-        .run()
+        .register_type::<Cannonball>();
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, States, Default)]
@@ -91,6 +101,21 @@ fn spawn_scene(mut commands: Commands, scene_handles: Res<SceneHandles>) {
     });
 }
 
+// 将在检查器中调整的组件写回场景文件
+fn write_extras(
+    scene: Query<Entity, With<Handle<Scene>>>,
+    mut write_extras_writer: EventWriter<WriteBlenderExtras>,
+) {
+    let Ok(entity) = scene.get_single() else {
+        return;
+    };
+    write_extras_writer.send(
+        WriteBlenderExtras::new(entity, "assets/blender_as_bevy_editor/barbette.glb")
+            .with::<ShadowsEnabled>()
+            .with::<Collider>(),
+    );
+}
+
 fn shadows_enabled(mut lights: Query<(&mut DirectionalLight, &ShadowsEnabled)>) {
     for (mut light, shadows_enabled) in &mut lights {
         if shadows_enabled.0 {
@@ -224,3 +249,24 @@ fn move_enemies(mut enemies: Query<&mut Transform, With<Enemy>>, time: Res<Time>
         transform.translation += velocity;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy_games::blender_editor::{
+        load_gltf_extras, reflect_scene_extras_report, ExtrasReflectContext,
+    };
+
+    use super::*;
+
+    #[test]
+    fn barbette_extras_are_valid() {
+        let mut app = App::new();
+        register_types(&mut app);
+        let context = ExtrasReflectContext::from_world(app.world_mut());
+        let mut scene =
+            load_gltf_extras("assets/blender_as_bevy_editor/barbette.glb".as_ref()).unwrap();
+        let report = reflect_scene_extras_report(&mut scene, context);
+
+        assert!(report.is_clean(), "{report}: {report:?}");
+    }
+}
@@ -1,11 +1,10 @@
-mod blender_editor;
 mod spawn_tank;
 mod control_tank;
 
 use bevy::{asset::AssetPath, dev_tools::fps_overlay::FpsOverlayPlugin, prelude::*};
 use bevy_blendy_cameras::BlendyCamerasPlugin;
+use bevy_games::blender_editor::BlenderEditorPlugin;
 use bevy_rapier3d::plugin::*;
-use blender_editor::BlenderEditorPlugin;
 
 fn main() -> AppExit {
     App::new()
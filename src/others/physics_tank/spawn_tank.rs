@@ -1,8 +1,9 @@
 use bevy::{prelude::*, render::mesh::VertexAttributeValues, scene::SceneInstanceReady, utils::HashMap};
 use bevy_blendy_cameras::OrbitCameraController;
+use bevy_games::blender_editor::{ExtrasValidationSettings, SceneHandles};
 use bevy_rapier3d::prelude::*;
 
-use crate::{blender_editor::SceneHandles, GameState};
+use crate::GameState;
 
 pub fn plugin(app: &mut App) {
     app.register_type::<EnableLightShadows>()
@@ -19,6 +20,8 @@ pub fn plugin(app: &mut App) {
         .register_type::<TankBody>()
         .register_type::<RigidBodyMarker>()
         .register_type::<ColliderMarker>()
+        // Blender 的布尔插件写入的自定义属性
+        .insert_resource(ExtrasValidationSettings::default().ignore_key("booleans"))
         .add_systems(Startup, spawn_camera)
         .add_systems(OnEnter(GameState::Start), spawn_scene)
         .add_systems(
@@ -316,3 +319,24 @@ fn link_body_and_wheels(
             ));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy_games::blender_editor::{
+        load_gltf_extras, reflect_scene_extras_report, ExtrasReflectContext,
+    };
+
+    use super::*;
+
+    #[test]
+    fn physics_tank_extras_are_valid() {
+        let mut app = App::new();
+        app.add_plugins(plugin);
+        let context = ExtrasReflectContext::from_world(app.world_mut());
+        let mut scene =
+            load_gltf_extras("assets/physics_tank/physics_tank.glb".as_ref()).unwrap();
+        let report = reflect_scene_extras_report(&mut scene, context);
+
+        assert!(report.is_clean(), "{report}: {report:?}");
+    }
+}
@@ -0,0 +1,203 @@
+use bevy::{audio::Volume, prelude::*, utils::HashMap};
+
+use crate::settings::GameSettings;
+
+// 背景音乐淡入淡出的时长
+const CROSSFADE_SECONDS: f32 = 1.5;
+
+pub fn plugin(app: &mut App) {
+    app.add_event::<PlaySound>()
+        .init_resource::<SoundEffects>()
+        .init_resource::<Music>()
+        .add_systems(
+            Update,
+            (
+                button_sounds,
+                play_sound_effects,
+                switch_music.run_if(resource_changed::<Music>),
+                fade_music,
+            )
+                .chain(),
+        );
+}
+
+// 音效
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SoundEffect {
+    Eat,
+    Jump,
+    Land,
+    Death,
+    ButtonHover,
+    ButtonPress,
+    LevelComplete,
+}
+
+impl SoundEffect {
+    const ALL: [Self; 7] = [
+        Self::Eat,
+        Self::Jump,
+        Self::Land,
+        Self::Death,
+        Self::ButtonHover,
+        Self::ButtonPress,
+        Self::LevelComplete,
+    ];
+
+    fn path(self) -> &'static str {
+        match self {
+            Self::Eat => "sounds/eat.ogg",
+            Self::Jump => "sounds/jump.ogg",
+            Self::Land => "sounds/land.ogg",
+            Self::Death => "sounds/death.ogg",
+            Self::ButtonHover => "sounds/button_hover.ogg",
+            Self::ButtonPress => "sounds/button_press.ogg",
+            Self::LevelComplete => "sounds/level_complete.ogg",
+        }
+    }
+}
+
+// 播放一个音效
+#[derive(Event, Debug, Clone, Copy)]
+pub struct PlaySound(pub SoundEffect);
+
+// 预先加载的音效
+#[derive(Resource)]
+struct SoundEffects(HashMap<SoundEffect, Handle<AudioSource>>);
+
+impl FromWorld for SoundEffects {
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.resource::<AssetServer>();
+        Self(
+            SoundEffect::ALL
+                .into_iter()
+                .map(|effect| (effect, asset_server.load(effect.path())))
+                .collect(),
+        )
+    }
+}
+
+// 背景音乐
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MusicTrack {
+    Menu,
+    Game,
+    Over,
+}
+
+impl MusicTrack {
+    fn path(self) -> &'static str {
+        match self {
+            Self::Menu => "music/menu.ogg",
+            Self::Game => "music/game.ogg",
+            Self::Over => "music/over.ogg",
+        }
+    }
+}
+
+// 当前应该播放的背景音乐，修改后淡出旧的音乐并淡入新的音乐
+#[derive(Resource, Debug, Default, PartialEq)]
+pub struct Music(Option<MusicTrack>);
+
+// 进入状态时切换背景音乐的系统
+pub fn play_music(track: MusicTrack) -> impl FnMut(ResMut<Music>) {
+    move |mut music: ResMut<Music>| {
+        music.set_if_neq(Music(Some(track)));
+    }
+}
+
+// 正在播放的背景音乐，fade 是淡入淡出的进度，淡出到 0 后删除
+#[derive(Component)]
+struct MusicPlayer {
+    track: MusicTrack,
+    fade: f32,
+    fading_out: bool,
+}
+
+// 按钮悬停和按下的音效
+fn button_sounds(
+    buttons: Query<&Interaction, (Changed<Interaction>, With<Button>)>,
+    mut sound_writer: EventWriter<PlaySound>,
+) {
+    for interaction in &buttons {
+        match interaction {
+            Interaction::Hovered => {
+                sound_writer.send(PlaySound(SoundEffect::ButtonHover));
+            }
+            Interaction::Pressed => {
+                sound_writer.send(PlaySound(SoundEffect::ButtonPress));
+            }
+            Interaction::None => (),
+        }
+    }
+}
+
+// 播放音效，播放完成后删除实体
+fn play_sound_effects(
+    mut commands: Commands,
+    mut sound_reader: EventReader<PlaySound>,
+    sound_effects: Res<SoundEffects>,
+    settings: Res<GameSettings>,
+) {
+    for PlaySound(effect) in sound_reader.read() {
+        commands.spawn(AudioBundle {
+            source: sound_effects.0[effect].clone(),
+            settings: PlaybackSettings::DESPAWN.with_volume(Volume::new(settings.sfx_volume)),
+        });
+    }
+}
+
+// 背景音乐改变后，淡出正在播放的音乐，生成新的音乐并淡入
+fn switch_music(
+    mut commands: Commands,
+    mut players: Query<&mut MusicPlayer>,
+    music: Res<Music>,
+    asset_server: Res<AssetServer>,
+) {
+    let mut playing = false;
+    for mut player in &mut players {
+        player.fading_out = Some(player.track) != music.0;
+        playing |= !player.fading_out;
+    }
+    let Some(track) = music.0 else {
+        return;
+    };
+    if !playing {
+        commands.spawn((
+            AudioBundle {
+                source: asset_server.load(track.path()),
+                settings: PlaybackSettings::LOOP.with_volume(Volume::ZERO),
+            },
+            MusicPlayer {
+                track,
+                fade: 0.,
+                fading_out: false,
+            },
+        ));
+    }
+}
+
+// 淡入淡出背景音乐，音量随音乐音量和总音量变化
+fn fade_music(
+    mut commands: Commands,
+    mut players: Query<(Entity, &mut MusicPlayer, Option<&AudioSink>)>,
+    settings: Res<GameSettings>,
+    time: Res<Time>,
+) {
+    let step = time.delta_seconds() / CROSSFADE_SECONDS;
+    for (entity, mut player, sink) in &mut players {
+        if player.fading_out {
+            player.fade = (player.fade - step).max(0.);
+            if player.fade == 0. {
+                commands.entity(entity).despawn();
+                continue;
+            }
+        } else {
+            player.fade = (player.fade + step).min(1.);
+        }
+        // 设置 AudioSink 的音量时不会再乘以 GlobalVolume，这里直接使用总音量
+        if let Some(sink) = sink {
+            sink.set_volume(player.fade * settings.music_volume * settings.master_volume);
+        }
+    }
+}
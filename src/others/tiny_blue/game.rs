@@ -1,39 +1,92 @@
+mod animation;
+mod autosave;
+mod camera;
 mod collider;
+mod controller;
+mod enemies;
+mod falling_blocks;
 mod introduction;
+mod level_complete;
+mod lives;
 mod menu;
 mod over;
+mod path_mover;
+mod pickups;
 mod playing;
+mod sounds;
 
 use bevy::{asset::RecursiveDependencyLoadState, prelude::*};
+use bevy_games::blender_editor::BlenderScenes;
 
-use crate::AppState;
+use crate::{
+    input::{Action, ActionInput},
+    levels::{CurrentLevel, Levels},
+    run_stats::RunStats,
+    AppState,
+};
+
+use self::animation::PlayerAnimation;
 
-// 游戏场景路径
-const SCENE_PATH: &str = "tiny_blue.glb";
 // 每个食物的分数
 const FOOD_SCORE: usize = 10;
 
 pub fn plugin(app: &mut App) {
+    // Plugins 最多支持 15 个元素的元组，按用途分组
     app.add_plugins((
-        introduction::plugin,
-        collider::plugin,
-        playing::plugin,
-        over::plugin,
-        menu::plugin,
+        (
+            introduction::plugin,
+            collider::plugin,
+            animation::plugin,
+            autosave::plugin,
+            camera::plugin,
+            controller::plugin,
+            sounds::plugin,
+        ),
+        (
+            path_mover::plugin,
+            enemies::plugin,
+            falling_blocks::plugin,
+            pickups::plugin,
+        ),
+        (
+            playing::plugin,
+            level_complete::plugin,
+            lives::plugin,
+            over::plugin,
+            menu::plugin,
+        ),
     ))
     .add_sub_state::<GamePhase>()
     .enable_state_scoped_entities::<GamePhase>()
     .add_systems(
         OnEnter(GamePhase::Loading),
-        (spawn_scene, insert_score, add_animation_graph),
+        (
+            (despawn_level_scene, request_level_scene).chain(),
+            (bank_score, insert_score_and_collected_foods).chain(),
+        ),
     )
+    .add_systems(OnEnter(AppState::Game), insert_run_stats)
     .add_systems(
         Update,
-        check_scene_load_state.run_if(in_state(GamePhase::Loading)),
+        (
+            (
+                return_on_scene_failure,
+                spawn_level_scene,
+                check_scene_load_state,
+            )
+                .chain()
+                .run_if(in_state(GamePhase::Loading)),
+            tick_run_time.run_if(in_state(GamePhase::Playing)),
+        ),
     )
     .add_systems(
         OnExit(AppState::Game),
-        (remove_player_animations, remove_score),
+        (
+            remove_score_and_collected_foods,
+            remove_game_over_reason,
+            remove_level_scene_handle,
+            remove_run_stats,
+        ),
     );
 }
 
@@ -43,96 +96,172 @@ pub fn plugin(app: &mut App) {
 enum GamePhase {
     #[default]
     Loading, // 加载游戏场景
-    Introduction, // 生成游戏介绍
-    Playing,      // 玩游戏
-    Over,         // 游戏结束
-    Menu,         // 游戏菜单
-}
-
-// 玩家动画索引和动画图句柄
-#[derive(Resource)]
-struct PlayerAnimation {
-    animation: AnimationNodeIndex,
-    graph: Handle<AnimationGraph>,
+    Introduction,  // 生成游戏介绍
+    Playing,       // 玩游戏
+    LevelComplete, // 关卡完成
+    Over,          // 游戏结束
+    Menu,          // 游戏菜单
 }
 
 // 玩家分数
 #[derive(Resource)]
 struct Score(usize);
 
+// 当前关卡已经吃掉的食物的名字
+#[derive(Resource, Default)]
+struct CollectedFoods(Vec<String>);
+
 // 分数文本
 #[derive(Component)]
 struct ScoreText;
 
+// 游戏结束的原因
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+enum GameOverReason {
+    AllLevelsCleared, // 全部通关
+    OutOfLives,       // 生命耗尽
+}
+
 // 返回开始菜单按钮
 #[derive(Component)]
 pub struct ReturnStartMenuButton;
 
-// 检查场景加载状态
-fn check_scene_load_state(
-    scene: Query<&Handle<Scene>>,
+// 当前关卡的场景
+#[derive(Component)]
+struct LevelScene;
+
+// 当前关卡请求加载的场景，反射完 Blender 自定义属性后才生成
+#[derive(Resource)]
+struct LevelSceneHandle(Handle<Scene>);
+
+// 场景的依赖加载完成并且反射完自定义属性后生成场景
+fn spawn_level_scene(
+    mut commands: Commands,
+    level_scene_handle: Option<Res<LevelSceneHandle>>,
+    spawned: Query<(), With<LevelScene>>,
+    blender_scenes: BlenderScenes,
     asset_server: Res<AssetServer>,
-    mut game_phase: ResMut<NextState<GamePhase>>,
 ) {
-    let Ok(scene) = scene.get_single() else {
+    let Some(level_scene_handle) = level_scene_handle else {
         return;
     };
+    let scene = &level_scene_handle.0;
+    if !spawned.is_empty() || !blender_scenes.is_processed(scene) {
+        return;
+    }
     if let RecursiveDependencyLoadState::Loaded =
         asset_server.recursive_dependency_load_state(scene)
     {
+        commands.spawn((
+            SceneBundle {
+                scene: scene.clone(),
+                ..default()
+            },
+            LevelScene,
+            StateScoped(AppState::Game),
+        ));
+    }
+}
+
+// 场景加载或者校验失败时返回开始菜单，释放场景以便下次重新请求
+fn return_on_scene_failure(
+    level_scene_handle: Option<Res<LevelSceneHandle>>,
+    mut blender_scenes: BlenderScenes,
+    mut app_state: ResMut<NextState<AppState>>,
+) {
+    let Some(level_scene_handle) = level_scene_handle else {
+        return;
+    };
+    let scene = &level_scene_handle.0;
+    let Some(failure) = blender_scenes.failure(scene) else {
+        return;
+    };
+    error!("level scene {:?} failed: {failure:?}", scene.path());
+    blender_scenes.release(scene);
+    app_state.set(AppState::StartMenu);
+}
+
+// 场景实例生成后进入游戏介绍
+fn check_scene_load_state(
+    scene: Query<(), (With<LevelScene>, With<Children>)>,
+    player_animation: Option<Res<PlayerAnimation>>,
+    mut game_phase: ResMut<NextState<GamePhase>>,
+) {
+    if !scene.is_empty() && player_animation.is_some() {
         game_phase.set(GamePhase::Introduction);
     }
 }
 
-// 生成场景
-fn spawn_scene(mut commands: Commands, asset_server: Res<AssetServer>) {
-    commands.spawn((
-        SceneBundle {
-            scene: asset_server.load(GltfAssetLabel::Scene(0).from_asset(SCENE_PATH)),
-            ..default()
-        },
-        StateScoped(AppState::Game),
-    ));
+// 删除上一关的场景
+fn despawn_level_scene(mut commands: Commands, scenes: Query<Entity, With<LevelScene>>) {
+    for entity in &scenes {
+        commands.entity(entity).despawn_recursive();
+    }
 }
 
-// 添加玩家的动画图资产，并插入 PlayerAnimation 资源
-fn add_animation_graph(
+// 请求加载当前关卡的场景，加载完成后将 Blender 自定义属性反射为组件
+fn request_level_scene(
     mut commands: Commands,
-    mut graphs: ResMut<Assets<AnimationGraph>>,
-    asset_server: Res<AssetServer>,
+    mut blender_scenes: BlenderScenes,
+    levels: Res<Levels>,
+    current_level: Res<CurrentLevel>,
 ) {
-    let mut graph = AnimationGraph::new();
-    let animation = graph.add_clip(
-        asset_server.load(GltfAssetLabel::Animation(0).from_asset(SCENE_PATH)),
-        1.,
-        graph.root,
-    );
-    let graph = graphs.add(graph);
-    commands.insert_resource(PlayerAnimation { animation, graph });
+    let scene_path = levels.get_level(*current_level).scene.clone();
+    let scene = blender_scenes.request(GltfAssetLabel::Scene(0).from_asset(scene_path));
+    commands.insert_resource(LevelSceneHandle(scene));
 }
 
-// 删除 PlayerAnimations 资源
-fn remove_player_animations(mut commands: Commands) {
-    commands.remove_resource::<PlayerAnimation>();
+// 删除当前关卡的场景句柄
+fn remove_level_scene_handle(mut commands: Commands) {
+    commands.remove_resource::<LevelSceneHandle>();
 }
 
-// 插入分数资源
-fn insert_score(mut commands: Commands) {
+// 插入整局游戏的统计资源
+fn insert_run_stats(mut commands: Commands) {
+    commands.insert_resource(RunStats::default());
+}
+
+// 删除整局游戏的统计资源
+fn remove_run_stats(mut commands: Commands) {
+    commands.remove_resource::<RunStats>();
+}
+
+// 游戏用时计时
+fn tick_run_time(mut run_stats: ResMut<RunStats>, time: Res<Time>) {
+    run_stats.seconds += time.delta_seconds();
+}
+
+// 进入下一关前，将上一关的分数计入总分
+fn bank_score(score: Option<Res<Score>>, mut run_stats: ResMut<RunStats>) {
+    if let Some(score) = score {
+        run_stats.banked_score += score.0;
+    }
+}
+
+// 插入分数和已吃掉食物资源
+fn insert_score_and_collected_foods(mut commands: Commands) {
     commands.insert_resource(Score(0));
+    commands.insert_resource(CollectedFoods::default());
 }
 
-// 删除分数资源
-fn remove_score(mut commands: Commands) {
+// 删除分数和已吃掉食物资源
+fn remove_score_and_collected_foods(mut commands: Commands) {
     commands.remove_resource::<Score>();
+    commands.remove_resource::<CollectedFoods>();
+}
+
+// 删除游戏结束原因资源
+fn remove_game_over_reason(mut commands: Commands) {
+    commands.remove_resource::<GameOverReason>();
 }
 
 // 打开或关闭菜单
 fn open_close_menu_page(
-    keyboard: Res<ButtonInput<KeyCode>>,
+    action_input: ActionInput,
     state: Res<State<GamePhase>>,
     mut next_state: ResMut<NextState<GamePhase>>,
 ) {
-    if keyboard.just_pressed(KeyCode::Space) {
+    if action_input.just_pressed(Action::Pause) {
         match state.get() {
             GamePhase::Playing => next_state.set(GamePhase::Menu),
             GamePhase::Menu => next_state.set(GamePhase::Playing),
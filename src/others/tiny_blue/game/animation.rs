@@ -0,0 +1,216 @@
+use std::time::Duration;
+
+use avian2d::prelude::LinearVelocity;
+use bevy::{gltf::Gltf, prelude::*, utils::HashMap};
+
+use crate::{
+    levels::{CurrentLevel, Levels},
+    AppState,
+};
+
+use super::{
+    collider::PlayerRigidBody, controller::CharacterController, lives::PlayerDying, GamePhase,
+};
+
+// 动画切换的混合时间
+const TRANSITION_DURATION: Duration = Duration::from_millis(200);
+// 落地动画的最短播放时间，之后可以被行走打断
+const LAND_SECONDS: f32 = 0.15;
+// 水平速度超过这个值时播放行走动画
+const WALK_SPEED_THRESHOLD: f32 = 0.2;
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(
+        OnEnter(GamePhase::Loading),
+        (remove_player_animation, load_player_gltf),
+    )
+    .add_systems(
+        Update,
+        build_player_animation
+            .run_if(in_state(GamePhase::Loading).and_then(not(resource_exists::<PlayerAnimation>))),
+    )
+    .add_systems(
+        Update,
+        update_player_animation.run_if(in_state(GamePhase::Playing)),
+    )
+    .add_systems(OnExit(AppState::Game), remove_player_animation);
+}
+
+// 玩家的动画状态
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PlayerAnimationState {
+    #[default]
+    Idle,
+    Walk,
+    Jump,
+    Fall,
+    Land,
+    Death,
+}
+
+impl PlayerAnimationState {
+    const ALL: [PlayerAnimationState; 6] = [
+        PlayerAnimationState::Idle,
+        PlayerAnimationState::Walk,
+        PlayerAnimationState::Jump,
+        PlayerAnimationState::Fall,
+        PlayerAnimationState::Land,
+        PlayerAnimationState::Death,
+    ];
+
+    // glTF 中对应的动画名
+    fn clip_name(self) -> &'static str {
+        match self {
+            PlayerAnimationState::Idle => "idle",
+            PlayerAnimationState::Walk => "walk",
+            PlayerAnimationState::Jump => "jump",
+            PlayerAnimationState::Fall => "fall",
+            PlayerAnimationState::Land => "land",
+            PlayerAnimationState::Death => "death",
+        }
+    }
+
+    // 循环播放的状态
+    fn is_looping(self) -> bool {
+        matches!(
+            self,
+            PlayerAnimationState::Idle | PlayerAnimationState::Walk | PlayerAnimationState::Fall
+        )
+    }
+}
+
+// 当前关卡场景的 glTF，用于按名字查找动画
+#[derive(Resource)]
+struct PlayerGltf(Handle<Gltf>);
+
+// 玩家的动画图和每个状态对应的动画节点，没有对应动画的状态不播放动画
+#[derive(Resource)]
+pub struct PlayerAnimation {
+    pub graph: Handle<AnimationGraph>,
+    nodes: HashMap<PlayerAnimationState, AnimationNodeIndex>,
+}
+
+// 玩家动画状态机，由 introduction 插入到玩家上
+#[derive(Component, Debug, Default)]
+pub struct PlayerAnimationMachine {
+    state: Option<PlayerAnimationState>,
+    // 落地动画已经播放的时间
+    land_seconds: f32,
+}
+
+// 加载当前关卡的 glTF
+fn load_player_gltf(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    levels: Res<Levels>,
+    current_level: Res<CurrentLevel>,
+) {
+    let scene_path = levels.get_level(*current_level).scene.clone();
+    commands.insert_resource(PlayerGltf(asset_server.load(scene_path)));
+}
+
+// glTF 加载完成后，按名字查找每个状态的动画并生成动画图
+// 没有名为 walk 的动画时使用第一个动画，兼容只有一个行走动画的旧场景
+fn build_player_animation(
+    mut commands: Commands,
+    player_gltf: Option<Res<PlayerGltf>>,
+    gltfs: Res<Assets<Gltf>>,
+    mut graphs: ResMut<Assets<AnimationGraph>>,
+) {
+    let Some(gltf) = player_gltf.and_then(|player_gltf| gltfs.get(&player_gltf.0)) else {
+        return;
+    };
+    let mut graph = AnimationGraph::new();
+    let mut nodes = HashMap::new();
+    for state in PlayerAnimationState::ALL {
+        let clip = gltf.named_animations.get(state.clip_name()).or_else(|| {
+            (state == PlayerAnimationState::Walk)
+                .then(|| gltf.animations.first())
+                .flatten()
+        });
+        match clip {
+            Some(clip) => {
+                nodes.insert(state, graph.add_clip(clip.clone(), 1., graph.root));
+            }
+            None => warn!("player animation `{}` not found", state.clip_name()),
+        }
+    }
+    commands.insert_resource(PlayerAnimation {
+        graph: graphs.add(graph),
+        nodes,
+    });
+}
+
+// 删除 PlayerAnimation 资源
+fn remove_player_animation(mut commands: Commands) {
+    commands.remove_resource::<PlayerAnimation>();
+}
+
+// 根据速度和是否站在地面上切换动画状态
+fn update_player_animation(
+    mut player: Query<
+        (
+            &mut PlayerAnimationMachine,
+            &mut AnimationTransitions,
+            &mut AnimationPlayer,
+            &CharacterController,
+            &LinearVelocity,
+            Has<PlayerDying>,
+        ),
+        With<PlayerRigidBody>,
+    >,
+    animation: Res<PlayerAnimation>,
+    time: Res<Time>,
+) {
+    let Ok((
+        mut machine,
+        mut animation_transitions,
+        mut animation_player,
+        controller,
+        linear_velocity,
+        dying,
+    )) = player.get_single_mut()
+    else {
+        return;
+    };
+    let previous = machine.state;
+    let walking = linear_velocity.x.abs() > WALK_SPEED_THRESHOLD;
+    let state = if dying {
+        PlayerAnimationState::Death
+    } else if !controller.is_grounded() {
+        if linear_velocity.y > 0. {
+            PlayerAnimationState::Jump
+        } else {
+            PlayerAnimationState::Fall
+        }
+    } else {
+        match previous {
+            Some(PlayerAnimationState::Jump | PlayerAnimationState::Fall) => {
+                machine.land_seconds = 0.;
+                PlayerAnimationState::Land
+            }
+            Some(PlayerAnimationState::Land) if machine.land_seconds < LAND_SECONDS => {
+                PlayerAnimationState::Land
+            }
+            _ if walking => PlayerAnimationState::Walk,
+            _ => PlayerAnimationState::Idle,
+        }
+    };
+    machine.land_seconds += time.delta_seconds();
+    if previous == Some(state) {
+        return;
+    }
+    machine.state = Some(state);
+    match animation.nodes.get(&state) {
+        Some(&node) => {
+            let active_animation =
+                animation_transitions.play(&mut animation_player, node, TRANSITION_DURATION);
+            if state.is_looping() {
+                active_animation.repeat();
+            }
+        }
+        None => {
+            animation_player.stop_all();
+        }
+    }
+}
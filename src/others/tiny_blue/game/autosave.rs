@@ -0,0 +1,194 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use crate::{
+    levels::{CurrentLevel, Levels},
+    run_stats::RunStats,
+    save::{PendingSave, SaveState},
+    AppState,
+};
+
+use super::{
+    collider::{DoorBody, FoodSensor, PlayerRigidBody},
+    controller::CharacterController,
+    lives::{Checkpoint, Lives, PlayerDying},
+    pickups::KeyRing,
+    CollectedFoods, GamePhase, Score,
+};
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(GamePhase::Menu), write_save)
+        .add_systems(OnEnter(GamePhase::LevelComplete), write_next_level_save)
+        .add_systems(OnEnter(GamePhase::Over), remove_save)
+        .add_systems(
+            OnEnter(GamePhase::Playing),
+            restore_save.run_if(resource_exists::<PendingSave>),
+        )
+        .add_systems(OnExit(AppState::Game), remove_pending_save)
+        .add_systems(
+            Last,
+            write_save_on_exit
+                .run_if(in_state(GamePhase::Playing).or_else(in_state(GamePhase::Menu))),
+        );
+}
+
+// 当前关卡的游戏进度
+#[derive(SystemParam)]
+struct GameProgress<'w, 's> {
+    player: Query<
+        'w,
+        's,
+        (
+            &'static Transform,
+            &'static CharacterController,
+            Has<PlayerDying>,
+        ),
+        With<PlayerRigidBody>,
+    >,
+    checkpoint: Res<'w, Checkpoint>,
+    current_level: Res<'w, CurrentLevel>,
+    score: Res<'w, Score>,
+    collected_foods: Res<'w, CollectedFoods>,
+    lives: Res<'w, Lives>,
+    run_stats: Res<'w, RunStats>,
+    key_ring: Res<'w, KeyRing>,
+}
+
+impl GameProgress<'_, '_> {
+    fn save_state(&self) -> SaveState {
+        // 玩家正在死亡或者在空中时保存检查点，继续游戏时不会回到危险的位置
+        let player_position =
+            self.player
+                .get_single()
+                .ok()
+                .and_then(|(transform, controller, dying)| {
+                    if dying || !controller.is_grounded() {
+                        self.checkpoint.0
+                    } else {
+                        Some(transform.translation.truncate())
+                    }
+                });
+        SaveState {
+            level: self.current_level.0,
+            score: self.score.0,
+            collected_foods: self.collected_foods.0.clone(),
+            player_position,
+            lives: self.lives.0,
+            run_stats: self.run_stats.clone(),
+            keys: self.key_ring.keys,
+            opened_doors: self.key_ring.opened_doors.clone(),
+            checkpoint: self.checkpoint.0,
+        }
+    }
+}
+
+// 保存当前的游戏进度
+fn write_save(game_progress: GameProgress) {
+    game_progress.save_state().save();
+}
+
+// 退出游戏时保存进度
+fn write_save_on_exit(mut exit_reader: EventReader<AppExit>, game_progress: GameProgress) {
+    if exit_reader.is_empty() {
+        return;
+    }
+    exit_reader.clear();
+    game_progress.save_state().save();
+}
+
+// 完成关卡后保存，继续游戏时从下一关开始
+fn write_next_level_save(
+    levels: Res<Levels>,
+    current_level: Res<CurrentLevel>,
+    score: Res<Score>,
+    lives: Res<Lives>,
+    run_stats: Res<RunStats>,
+) {
+    let Some(next_level) = levels.next(*current_level) else {
+        return;
+    };
+    SaveState {
+        level: next_level.0,
+        score: 0,
+        collected_foods: Vec::new(),
+        player_position: None,
+        lives: lives.0,
+        run_stats: RunStats {
+            banked_score: run_stats.total_score(score.0),
+            ..run_stats.clone()
+        },
+        keys: 0,
+        opened_doors: Vec::new(),
+        checkpoint: None,
+    }
+    .save();
+}
+
+// 游戏结束后删除存档
+fn remove_save() {
+    SaveState::remove();
+}
+
+// 继续游戏时从存档恢复的游戏进度
+#[derive(SystemParam)]
+struct RestoredProgress<'w> {
+    score: ResMut<'w, Score>,
+    collected_foods: ResMut<'w, CollectedFoods>,
+    lives: ResMut<'w, Lives>,
+    run_stats: ResMut<'w, RunStats>,
+    key_ring: ResMut<'w, KeyRing>,
+    checkpoint: ResMut<'w, Checkpoint>,
+}
+
+// 恢复存档中的分数、生命、已经吃掉的食物、钥匙、已经打开的门、检查点和玩家位置
+fn restore_save(
+    mut commands: Commands,
+    pending_save: Res<PendingSave>,
+    foods: Query<(Entity, &Name), With<FoodSensor>>,
+    doors: Query<(Entity, &Name), With<DoorBody>>,
+    mut player: Query<&mut Transform, With<PlayerRigidBody>>,
+    mut progress: RestoredProgress,
+) {
+    progress.score.0 = pending_save.score;
+    progress.run_stats.clone_from(&pending_save.run_stats);
+    progress.lives.0 = pending_save.lives;
+    progress
+        .collected_foods
+        .0
+        .clone_from(&pending_save.collected_foods);
+    for (entity, name) in &foods {
+        if progress
+            .collected_foods
+            .0
+            .iter()
+            .any(|food| food == name.as_str())
+        {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+    progress.key_ring.keys = pending_save.keys;
+    progress
+        .key_ring
+        .opened_doors
+        .clone_from(&pending_save.opened_doors);
+    for (entity, name) in &doors {
+        if progress
+            .key_ring
+            .opened_doors
+            .iter()
+            .any(|door| door == name.as_str())
+        {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+    progress.checkpoint.0 = pending_save.checkpoint.or(pending_save.player_position);
+    if let (Ok(mut transform), Some(position)) =
+        (player.get_single_mut(), pending_save.player_position)
+    {
+        transform.translation = position.extend(transform.translation.z);
+    }
+    commands.remove_resource::<PendingSave>();
+}
+
+fn remove_pending_save(mut commands: Commands) {
+    commands.remove_resource::<PendingSave>();
+}
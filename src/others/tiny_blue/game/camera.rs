@@ -0,0 +1,150 @@
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+use super::{collider::PlayerRigidBody, lives::PlayerDying, GamePhase};
+
+pub fn plugin(app: &mut App) {
+    app.register_type::<CameraBounds>()
+        .init_resource::<CameraSettings>()
+        .add_systems(
+            Update,
+            (
+                init_follow_camera.run_if(
+                    in_state(GamePhase::Introduction).or_else(in_state(GamePhase::Playing)),
+                ),
+                (shake_on_death, follow_player)
+                    .chain()
+                    .run_if(in_state(GamePhase::Playing)),
+            ),
+        );
+}
+
+// 跟随相机的参数
+#[derive(Resource, Debug, Clone)]
+pub struct CameraSettings {
+    // 死区的半宽和半高，玩家在死区内移动时相机不动
+    pub dead_zone: Vec2,
+    // 向移动方向看的距离
+    pub look_ahead: f32,
+    // 看向前方的偏移变化的速度
+    pub look_ahead_speed: f32,
+    // 相机追上目标位置的速度，越大越快
+    pub smoothing: f32,
+    // 死亡时相机抖动的时长和幅度
+    pub shake_seconds: f32,
+    pub shake_strength: f32,
+}
+
+impl Default for CameraSettings {
+    fn default() -> Self {
+        Self {
+            dead_zone: Vec2::new(0.4, 0.3),
+            look_ahead: 0.8,
+            look_ahead_speed: 2.,
+            smoothing: 5.,
+            shake_seconds: 0.4,
+            shake_strength: 0.15,
+        }
+    }
+}
+
+// 相机中心可以移动的范围，在 Blender 中作为场景的自定义属性添加
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[reflect(Component)]
+pub struct CameraBounds {
+    pub min: Vec2,
+    pub max: Vec2,
+}
+
+// 跟随玩家的相机，保持场景中相机与玩家初始位置的偏移
+#[derive(Component)]
+struct FollowCamera {
+    // 相机相对于关注点的偏移
+    offset: Vec3,
+    // 关注点，玩家离开死区时移动
+    focus: Vec2,
+    // 当前看向前方的偏移
+    look_ahead: f32,
+    // 没有抖动时相机的位置
+    position: Vec2,
+    shake: Timer,
+}
+
+// 场景中的相机改为跟随相机
+fn init_follow_camera(
+    mut commands: Commands,
+    cameras: Query<(Entity, &Transform), (With<Camera3d>, Without<FollowCamera>)>,
+    player: Query<&Position, With<PlayerRigidBody>>,
+) {
+    let Ok(player_position) = player.get_single() else {
+        return;
+    };
+    for (entity, transform) in &cameras {
+        let mut shake = Timer::from_seconds(0., TimerMode::Once);
+        shake.tick(shake.duration());
+        commands.entity(entity).insert(FollowCamera {
+            offset: transform.translation - player_position.0.extend(0.),
+            focus: player_position.0,
+            look_ahead: 0.,
+            position: transform.translation.truncate(),
+            shake,
+        });
+    }
+}
+
+// 玩家开始死亡时抖动相机
+fn shake_on_death(
+    dying: Query<(), (With<PlayerRigidBody>, Added<PlayerDying>)>,
+    mut cameras: Query<&mut FollowCamera>,
+    settings: Res<CameraSettings>,
+) {
+    if dying.is_empty() {
+        return;
+    }
+    for mut camera in &mut cameras {
+        camera.shake = Timer::from_seconds(settings.shake_seconds, TimerMode::Once);
+    }
+}
+
+// 相机跟随玩家，移出死区后移动关注点，向移动方向多看一段距离，平滑移动并限制在关卡范围内
+fn follow_player(
+    mut cameras: Query<(&mut FollowCamera, &mut Transform)>,
+    player: Query<(&Position, &LinearVelocity), With<PlayerRigidBody>>,
+    bounds: Query<&CameraBounds>,
+    settings: Res<CameraSettings>,
+    time: Res<Time>,
+) {
+    let Ok((player_position, linear_velocity)) = player.get_single() else {
+        return;
+    };
+    let delta = time.delta_seconds();
+    for (mut camera, mut transform) in &mut cameras {
+        let focus = camera.focus;
+        let offset = player_position.0 - focus;
+        camera.focus += offset - offset.clamp(-settings.dead_zone, settings.dead_zone);
+
+        let look_ahead = if linear_velocity.x.abs() > 0.1 {
+            linear_velocity.x.signum() * settings.look_ahead
+        } else {
+            camera.look_ahead
+        };
+        let max_delta = settings.look_ahead_speed * delta;
+        camera.look_ahead += (look_ahead - camera.look_ahead).clamp(-max_delta, max_delta);
+
+        let mut target = camera.focus + Vec2::new(camera.look_ahead, 0.) + camera.offset.truncate();
+        if let Ok(bounds) = bounds.get_single() {
+            // 边界来自 Blender 中的数据，min 和 max 可能写反
+            target = target.clamp(bounds.min.min(bounds.max), bounds.min.max(bounds.max));
+        }
+        let position = camera.position;
+        camera.position = position.lerp(target, 1. - (-settings.smoothing * delta).exp());
+
+        let mut shake = Vec2::ZERO;
+        if !camera.shake.tick(time.delta()).finished() {
+            let strength = settings.shake_strength * camera.shake.fraction_remaining();
+            shake =
+                Vec2::new(rand::random::<f32>() - 0.5, rand::random::<f32>() - 0.5) * 2. * strength;
+        }
+        transform.translation = (camera.position + shake).extend(transform.translation.z);
+    }
+}
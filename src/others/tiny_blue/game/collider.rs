@@ -1,7 +1,11 @@
 use avian2d::prelude::*;
-use bevy::{prelude::*, render::mesh::VertexAttributeValues};
+use bevy::{
+    ecs::system::{EntityCommands, SystemParam},
+    prelude::*,
+    render::mesh::VertexAttributeValues,
+};
 
-use super::GamePhase;
+use super::{controller::CharacterController, GamePhase};
 
 pub fn plugin(app: &mut App) {
     app.add_systems(
@@ -26,206 +30,396 @@ pub struct FirePitSensor;
 #[derive(Component)]
 pub struct FoodSensor;
 
-// 根据场景中专门用于生成碰撞体的网格，生成碰撞体
+// 检查点传感器
+#[derive(Component)]
+pub struct CheckpointSensor;
+
+// 敌人刚体
+#[derive(Component)]
+pub struct EnemyBody;
+
+// 掉落方块刚体
+#[derive(Component)]
+pub struct FallingBlockBody;
+
+// 门刚体，带着钥匙碰到时打开
+#[derive(Component)]
+pub struct DoorBody;
+
+// 碰撞体网格所在的节点结构
+#[derive(Debug, Clone, Copy)]
+enum ColliderLayout {
+    // 节点是一个组，第一个子节点是显示的物体，其余子节点是碰撞体网格
+    // 刚体插入到显示的物体上并移出组，只有一个碰撞体网格时直接插入碰撞体，否则生成子碰撞体
+    // 传感器的每个子碰撞体都是传感器，标记组件只插入到刚体上
+    Group,
+    // 节点本身是碰撞体网格，在父节点下生成一个独立的刚体，随关卡场景一起删除
+    Standalone,
+    // 节点本身是碰撞体网格，刚体插入到父节点上
+    Parent,
+}
+
+// 场景节点与刚体的对应规则，节点名以 prefix 开头时使用
+struct ColliderRule {
+    prefix: &'static str,
+    layout: ColliderLayout,
+    rigid_body: RigidBody,
+    sensor: bool,
+    // 插入标记组件等
+    insert: fn(&mut EntityCommands),
+}
+
+// 新的危险物或拾取物只需要在这里添加规则
+const COLLIDER_RULES: &[ColliderRule] = &[
+    ColliderRule {
+        prefix: "wall_object",
+        layout: ColliderLayout::Group,
+        rigid_body: RigidBody::Static,
+        sensor: false,
+        insert: |_| {},
+    },
+    ColliderRule {
+        prefix: "spike_collider_object",
+        layout: ColliderLayout::Standalone,
+        rigid_body: RigidBody::Static,
+        sensor: true,
+        insert: |entity_commands| {
+            entity_commands.insert(SpikeSensor);
+        },
+    },
+    ColliderRule {
+        prefix: "fire_pit_object",
+        layout: ColliderLayout::Group,
+        rigid_body: RigidBody::Static,
+        sensor: true,
+        insert: |entity_commands| {
+            entity_commands.insert(FirePitSensor);
+        },
+    },
+    ColliderRule {
+        prefix: "food_object",
+        layout: ColliderLayout::Group,
+        rigid_body: RigidBody::Static,
+        sensor: true,
+        insert: |entity_commands| {
+            entity_commands.insert(FoodSensor);
+        },
+    },
+    ColliderRule {
+        prefix: "checkpoint_object",
+        layout: ColliderLayout::Group,
+        rigid_body: RigidBody::Static,
+        sensor: true,
+        insert: |entity_commands| {
+            entity_commands.insert(CheckpointSensor);
+        },
+    },
+    ColliderRule {
+        prefix: "door_object",
+        layout: ColliderLayout::Group,
+        rigid_body: RigidBody::Static,
+        sensor: false,
+        insert: |entity_commands| {
+            entity_commands.insert(DoorBody);
+        },
+    },
+    // 敌人、移动平台和掉落方块是运动学刚体，路径等参数在 Blender 中通过自定义属性反射为组件
+    ColliderRule {
+        prefix: "enemy_object",
+        layout: ColliderLayout::Group,
+        rigid_body: RigidBody::Kinematic,
+        sensor: false,
+        insert: |entity_commands| {
+            entity_commands.insert(EnemyBody);
+        },
+    },
+    ColliderRule {
+        prefix: "moving_platform_object",
+        layout: ColliderLayout::Group,
+        rigid_body: RigidBody::Kinematic,
+        sensor: false,
+        insert: |_| {},
+    },
+    ColliderRule {
+        prefix: "falling_block_object",
+        layout: ColliderLayout::Group,
+        rigid_body: RigidBody::Kinematic,
+        sensor: false,
+        insert: |entity_commands| {
+            entity_commands.insert(FallingBlockBody);
+        },
+    },
+    ColliderRule {
+        prefix: "player_collider_object",
+        layout: ColliderLayout::Parent,
+        rigid_body: RigidBody::Dynamic,
+        sensor: false,
+        insert: |entity_commands| {
+            entity_commands.insert((
+                LockedAxes::ROTATION_LOCKED,
+                CharacterController::default(),
+                PlayerRigidBody,
+            ));
+        },
+    },
+];
+
+// 生成碰撞体需要查询的数据
+#[derive(SystemParam)]
+struct ColliderMeshes<'w, 's> {
+    transforms: Query<'w, 's, &'static Transform>,
+    children: Query<'w, 's, &'static Children>,
+    mesh_handles: Query<'w, 's, &'static Handle<Mesh>>,
+    meshes: Res<'w, Assets<Mesh>>,
+}
+
+impl ColliderMeshes<'_, '_> {
+    // 节点的平移，只使用 x 和 y
+    fn translation(&self, entity: Entity) -> Vec2 {
+        self.transforms
+            .get(entity)
+            .map_or(Vec2::ZERO, |transform| transform.translation.truncate())
+    }
+
+    // 根据碰撞体节点子实体的网格顶点生成凸包，offset 是顶点的偏移
+    fn convex_hull(&self, shape: Entity, offset: Vec2) -> Result<Collider, String> {
+        let mesh = self
+            .children
+            .get(shape)
+            .ok()
+            .and_then(|children| {
+                children
+                    .iter()
+                    .find_map(|&child| self.mesh_handles.get(child).ok())
+            })
+            .and_then(|mesh_handle| self.meshes.get(mesh_handle))
+            .ok_or_else(|| format!("collider shape {shape} has no loaded mesh"))?;
+        let Some(VertexAttributeValues::Float32x3(positions)) =
+            mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+        else {
+            return Err(format!("collider shape {shape} mesh has no positions"));
+        };
+        let positions = positions
+            .iter()
+            .map(|position| Vec2::new(position[0], position[1]) + offset)
+            .collect();
+        Collider::convex_hull(positions)
+            .ok_or_else(|| format!("collider shape {shape} convex hull failed"))
+    }
+}
+
+// 节点名对应的规则
+fn collider_rule(name: &str) -> Option<&'static ColliderRule> {
+    COLLIDER_RULES
+        .iter()
+        .find(|rule| name.starts_with(rule.prefix))
+}
+
+// 根据场景中专门用于生成碰撞体的网格，按照 COLLIDER_RULES 生成刚体和传感器
 fn spawn_rigid_body_and_sensor(
     mut commands: Commands,
-    objects: Query<(&Parent, Entity, &Children, &Name)>,
-    collider_objects: Query<(&Children, &Transform), Without<Handle<Mesh>>>,
-    mesh_objects: Query<&Handle<Mesh>>,
-    meshes: Res<Assets<Mesh>>,
+    objects: Query<(Entity, &Name, &Parent), Without<Handle<Mesh>>>,
+    collider_meshes: ColliderMeshes,
 ) {
-    for (parent, entity, children, name) in &objects {
-        // 生成墙壁刚体
-        if name.starts_with("wall_object") {
-            if children.len() == 2 {
-                if let Ok((collider_children, _)) = collider_objects.get(children[1]) {
-                    if let Ok(mesh_handle) = mesh_objects.get(collider_children[0]) {
-                        if let Some(mesh) = meshes.get(mesh_handle) {
-                            if let Some(VertexAttributeValues::Float32x3(positions)) =
-                                mesh.attribute(Mesh::ATTRIBUTE_POSITION)
-                            {
-                                let positions = positions
-                                    .iter()
-                                    .map(|position| Vec2::new(position[0], position[1]))
-                                    .collect();
-                                commands
-                                    .entity(children[0])
-                                    .insert((
-                                        RigidBody::Static,
-                                        Collider::convex_hull(positions).unwrap(),
-                                    ))
-                                    .set_parent_in_place(parent.get());
-                                let mut entity_commands = commands.entity(entity);
-                                entity_commands.remove_parent();
-                                entity_commands.despawn_recursive();
-                            }
-                        }
-                    }
-                }
-            } else {
-                let mut sub_colliders = Vec::new();
-                for &collider_entity in children.iter().skip(1) {
-                    if let Ok((collider_children, transform)) =
-                        collider_objects.get(collider_entity)
-                    {
-                        if let Ok(mesh_handle) = mesh_objects.get(collider_children[0]) {
-                            if let Some(mesh) = meshes.get(mesh_handle) {
-                                if let Some(VertexAttributeValues::Float32x3(positions)) =
-                                    mesh.attribute(Mesh::ATTRIBUTE_POSITION)
-                                {
-                                    let translation = transform.translation;
-                                    let positions = positions
-                                        .iter()
-                                        .map(|position| {
-                                            Vec2::new(
-                                                position[0] + translation.x,
-                                                position[1] + translation.y,
-                                            )
-                                        })
-                                        .collect();
-                                    sub_colliders.push(
-                                        commands
-                                            .spawn(Collider::convex_hull(positions).unwrap())
-                                            .id(),
-                                    );
-                                }
-                            }
-                        }
-                    }
-                }
-                commands
-                    .entity(children[0])
-                    .insert(RigidBody::Static)
-                    .push_children(&sub_colliders)
-                    .set_parent_in_place(parent.get());
-                let mut entity_commands = commands.entity(entity);
-                entity_commands.remove_parent();
-                entity_commands.despawn_recursive();
+    for (entity, name, parent) in &objects {
+        let Some(rule) = collider_rule(name) else {
+            // 组里的节点由组的规则处理
+            let in_group = objects.get(parent.get()).is_ok_and(|(_, parent_name, _)| {
+                collider_rule(parent_name)
+                    .is_some_and(|rule| matches!(rule.layout, ColliderLayout::Group))
+            });
+            if !in_group && (name.contains("_object") || name.contains("collider")) {
+                warn!("{entity} ({name}) looks like a collider object but matches no rule");
             }
+            continue;
+        };
+        if let Err(message) =
+            spawn_rule_collider(&mut commands, rule, entity, parent.get(), &collider_meshes)
+        {
+            warn!(
+                "{entity} ({name}) does not match the {:?} layout of `{}`: {message}",
+                rule.layout, rule.prefix
+            );
         }
+    }
+}
 
-        // 生成尖刺传感器
-        if name.starts_with("spike_collider_object") {
-            if let Ok((children, transform)) = collider_objects.get(entity) {
-                if let Ok(mesh_handle) = mesh_objects.get(children[0]) {
-                    if let Some(mesh) = meshes.get(mesh_handle) {
-                        if let Some(VertexAttributeValues::Float32x3(positions)) =
-                            mesh.attribute(Mesh::ATTRIBUTE_POSITION)
-                        {
-                            let translation = transform.translation;
-                            let positions = positions
-                                .iter()
-                                .map(|position| {
-                                    Vec2::new(
-                                        position[0] + translation.x,
-                                        position[1] + translation.y,
-                                    )
-                                })
-                                .collect();
-                            commands.spawn((
-                                RigidBody::Static,
-                                Collider::convex_hull(positions).unwrap(),
-                                Sensor,
-                                SpikeSensor,
-                            ));
-                            commands.entity(entity).despawn_recursive();
-                        }
-                    }
-                }
+// 按照规则生成一个节点的刚体
+fn spawn_rule_collider(
+    commands: &mut Commands,
+    rule: &ColliderRule,
+    entity: Entity,
+    parent: Entity,
+    collider_meshes: &ColliderMeshes,
+) -> Result<(), String> {
+    let body = match rule.layout {
+        ColliderLayout::Group => {
+            let children = collider_meshes
+                .children
+                .get(entity)
+                .map_err(|_| "group has no children".to_string())?;
+            let [body, shapes @ ..] = &children[..] else {
+                return Err("group has no children".into());
+            };
+            if shapes.is_empty() {
+                return Err("group has no collider shapes after the body".into());
             }
-        }
-
-        // 生成火坑传感器
-        if name.starts_with("fire_pit_object") {
-            if let Ok((collider_children, _)) = collider_objects.get(children[1]) {
-                if let Ok(mesh_handle) = mesh_objects.get(collider_children[0]) {
-                    if let Some(mesh) = meshes.get(mesh_handle) {
-                        if let Some(VertexAttributeValues::Float32x3(positions)) =
-                            mesh.attribute(Mesh::ATTRIBUTE_POSITION)
-                        {
-                            let positions = positions
-                                .iter()
-                                .map(|position| Vec2::new(position[0], position[1]))
-                                .collect();
-                            commands
-                                .entity(children[0])
-                                .insert((
-                                    RigidBody::Static,
-                                    Collider::convex_hull(positions).unwrap(),
-                                    Sensor,
-                                    FirePitSensor,
-                                ))
-                                .set_parent_in_place(parent.get());
-                            let mut entity_commands = commands.entity(children[1]);
-                            entity_commands.remove_parent();
-                            entity_commands.despawn_recursive();
+            // 只有一个碰撞体网格时直接使用网格顶点，子碰撞体的顶点加上碰撞体网格的平移
+            let colliders = if let [shape] = shapes {
+                vec![collider_meshes.convex_hull(*shape, Vec2::ZERO)?]
+            } else {
+                shapes
+                    .iter()
+                    .map(|&shape| {
+                        collider_meshes.convex_hull(shape, collider_meshes.translation(shape))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?
+            };
+            commands.entity(*body).set_parent_in_place(parent);
+            if let [collider] = &colliders[..] {
+                commands.entity(*body).insert(collider.clone());
+            } else {
+                let sub_colliders: Vec<_> = colliders
+                    .into_iter()
+                    .map(|collider| {
+                        let mut sub_collider = commands.spawn(collider);
+                        if rule.sensor {
+                            sub_collider.insert(Sensor);
                         }
-                    }
-                }
+                        sub_collider.id()
+                    })
+                    .collect();
+                commands.entity(*body).push_children(&sub_colliders);
             }
+            *body
         }
-
-        // 生成食物传感器
-        if name.starts_with("food_object") {
-            if let Ok((collider_children, _)) = collider_objects.get(children[1]) {
-                if let Ok(mesh_handle) = mesh_objects.get(collider_children[0]) {
-                    if let Some(mesh) = meshes.get(mesh_handle) {
-                        if let Some(VertexAttributeValues::Float32x3(positions)) =
-                            mesh.attribute(Mesh::ATTRIBUTE_POSITION)
-                        {
-                            let positions = positions
-                                .iter()
-                                .map(|position| Vec2::new(position[0], position[1]))
-                                .collect();
-                            commands
-                                .entity(children[0])
-                                .insert((
-                                    RigidBody::Static,
-                                    Collider::convex_hull(positions).unwrap(),
-                                    Sensor,
-                                    FoodSensor,
-                                ))
-                                .set_parent_in_place(parent.get());
-                            let mut entity_commands = commands.entity(children[1]);
-                            entity_commands.remove_parent();
-                            entity_commands.despawn_recursive();
-                        }
-                    }
-                }
-            }
+        ColliderLayout::Standalone => {
+            let collider =
+                collider_meshes.convex_hull(entity, collider_meshes.translation(entity))?;
+            commands
+                .spawn((collider, TransformBundle::default()))
+                .set_parent(parent)
+                .id()
+        }
+        ColliderLayout::Parent => {
+            let collider =
+                collider_meshes.convex_hull(entity, collider_meshes.translation(entity))?;
+            commands.entity(parent).insert(collider);
+            parent
         }
+    };
+    let mut body_commands = commands.entity(body);
+    body_commands.insert(rule.rigid_body);
+    if rule.sensor {
+        body_commands.insert(Sensor);
+    }
+    (rule.insert)(&mut body_commands);
+    let mut entity_commands = commands.entity(entity);
+    entity_commands.remove_parent();
+    entity_commands.despawn_recursive();
+    Ok(())
+}
 
-        // 生成玩家刚体
-        if name.starts_with("player_collider_object") {
-            if let Ok((children, transform)) = collider_objects.get(entity) {
-                if let Ok(mesh_handle) = mesh_objects.get(children[0]) {
-                    if let Some(mesh) = meshes.get(mesh_handle) {
-                        if let Some(VertexAttributeValues::Float32x3(positions)) =
-                            mesh.attribute(Mesh::ATTRIBUTE_POSITION)
-                        {
-                            let translation = transform.translation;
-                            let positions = positions
-                                .iter()
-                                .map(|position| {
-                                    Vec2::new(
-                                        position[0] + translation.x,
-                                        position[1] + translation.y,
-                                    )
-                                })
-                                .collect();
-                            commands.entity(parent.get()).insert((
-                                RigidBody::Dynamic,
-                                Collider::convex_hull(positions).unwrap(),
-                                LockedAxes::ROTATION_LOCKED,
-                                PlayerRigidBody,
-                            ));
-                            let mut entity_commands = commands.entity(entity);
-                            entity_commands.remove_parent();
-                            entity_commands.despawn_recursive();
-                        }
-                    }
-                }
-            }
+// 碰撞双方的刚体，子碰撞体使用它所在的刚体，标记组件都插入在刚体上
+fn contact_bodies(contacts: &Contacts) -> (Entity, Entity) {
+    (
+        contacts.body_entity1.unwrap_or(contacts.entity1),
+        contacts.body_entity2.unwrap_or(contacts.entity2),
+    )
+}
+
+// 玩家与另一个刚体碰撞时，返回另一个刚体
+pub fn other_body(contacts: &Contacts, player_entity: Entity) -> Option<Entity> {
+    match contact_bodies(contacts) {
+        (body1, body2) if body1 == player_entity => Some(body2),
+        (body1, body2) if body2 == player_entity => Some(body1),
+        _ => None,
+    }
+}
+
+// 玩家与另一个刚体碰撞时，返回另一个刚体和指向玩家外侧的接触法线
+pub fn player_contact(
+    contacts: &Contacts,
+    player_entity: Entity,
+    player_rotation: &Rotation,
+) -> Option<(Entity, Vec2)> {
+    let manifold = contacts.manifolds.first()?;
+    let (body1, body2) = contact_bodies(contacts);
+    if body1 == player_entity {
+        Some((body2, manifold.global_normal1(player_rotation)))
+    } else if body2 == player_entity {
+        Some((body1, manifold.global_normal2(player_rotation)))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        ecs::system::RunSystemOnce,
+        render::{mesh::PrimitiveTopology, render_asset::RenderAssetUsages},
+    };
+
+    use super::*;
+
+    // 碰撞体网格节点，网格在它的子实体上
+    fn spawn_shape(world: &mut World, mesh: &Handle<Mesh>, translation: Vec3) -> Entity {
+        world
+            .spawn((
+                Name::new("food_shape"),
+                SpatialBundle::from_transform(Transform::from_translation(translation)),
+            ))
+            .with_children(|parent| {
+                parent.spawn((mesh.clone(), SpatialBundle::default()));
+            })
+            .id()
+    }
+
+    #[test]
+    fn spawns_sensor_sub_colliders_for_two_shape_food_group() {
+        let mut world = World::new();
+        let mut meshes = Assets::<Mesh>::default();
+        let mesh = meshes.add(
+            Mesh::new(
+                PrimitiveTopology::TriangleList,
+                RenderAssetUsages::default(),
+            )
+            .with_inserted_attribute(
+                Mesh::ATTRIBUTE_POSITION,
+                vec![[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]],
+            ),
+        );
+        world.insert_resource(meshes);
+        let level = world.spawn(SpatialBundle::default()).id();
+        let body = world
+            .spawn((Name::new("food"), SpatialBundle::default()))
+            .id();
+        let shapes = [
+            spawn_shape(&mut world, &mesh, Vec3::ZERO),
+            spawn_shape(&mut world, &mesh, Vec3::X * 2.),
+        ];
+        let group = world
+            .spawn((Name::new("food_object.001"), SpatialBundle::default()))
+            .set_parent(level)
+            .push_children(&[body])
+            .push_children(&shapes)
+            .id();
+        world.run_system_once(spawn_rigid_body_and_sensor);
+
+        assert!(world.get_entity(group).is_none());
+        let body_ref = world.entity(body);
+        assert_eq!(body_ref.get::<Parent>().map(Parent::get), Some(level));
+        assert_eq!(body_ref.get::<RigidBody>(), Some(&RigidBody::Static));
+        assert!(body_ref.contains::<FoodSensor>());
+        assert!(!body_ref.contains::<Collider>());
+        let sub_colliders = body_ref.get::<Children>().unwrap();
+        assert_eq!(sub_colliders.len(), 2);
+        // 每个子碰撞体都是传感器，碰撞时通过刚体找到食物
+        for &sub_collider in sub_colliders {
+            let sub_collider = world.entity(sub_collider);
+            assert!(sub_collider.contains::<Collider>());
+            assert!(sub_collider.contains::<Sensor>());
         }
     }
 }
@@ -0,0 +1,223 @@
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use serde::Serialize;
+
+use crate::{
+    audio::{PlaySound, SoundEffect},
+    input::{Action, ActionInput},
+    run_stats::RunStats,
+};
+
+use super::{collider::PlayerRigidBody, lives::PlayerDying, pickups::Boosts, GamePhase};
+
+pub fn plugin(app: &mut App) {
+    app.init_resource::<ControllerSettings>().add_systems(
+        Update,
+        (update_grounded, player_movement, player_jump)
+            .chain()
+            .run_if(in_state(GamePhase::Playing)),
+    );
+}
+
+// 角色控制器的参数
+#[derive(Resource, Debug, Clone, Serialize)]
+pub struct ControllerSettings {
+    // 最大水平速度
+    pub max_speed: f32,
+    // 地面上的加速度和没有输入时的摩擦减速度
+    pub ground_acceleration: f32,
+    pub ground_friction: f32,
+    // 空中的加速度和没有输入时的阻力减速度
+    pub air_acceleration: f32,
+    pub air_friction: f32,
+    // 起跳速度
+    pub jump_speed: f32,
+    // 上升时松开跳跃键，竖直速度乘以这个系数，实现可变跳跃高度
+    pub jump_cut: f32,
+    // 离开地面后仍然可以起跳的时间
+    pub coyote_time: f32,
+    // 落地前按下跳跃键，落地后仍然会起跳的时间
+    pub jump_buffer_time: f32,
+    // 可以站立的最大坡度，单位是弧度
+    pub max_slope_angle: f32,
+    // 向下检测地面的距离
+    pub ground_distance: f32,
+}
+
+impl Default for ControllerSettings {
+    fn default() -> Self {
+        Self {
+            max_speed: 3.,
+            ground_acceleration: 30.,
+            ground_friction: 40.,
+            air_acceleration: 15.,
+            air_friction: 3.,
+            jump_speed: 4.5,
+            jump_cut: 0.5,
+            coyote_time: 0.1,
+            jump_buffer_time: 0.12,
+            max_slope_angle: 50_f32.to_radians(),
+            ground_distance: 0.05,
+        }
+    }
+}
+
+// 角色控制器的状态，由碰撞体规则插入到玩家刚体上
+#[derive(Component, Debug, Default)]
+pub struct CharacterController {
+    // 站立的地面法线，不在地面上时为 None
+    pub ground_normal: Option<Vec2>,
+    // 站立的地面的速度，站在移动平台上时跟随平台移动
+    pub ground_velocity: Vec2,
+    // 剩余的土狼时间
+    coyote_left: f32,
+    // 剩余的跳跃缓冲时间
+    jump_buffer_left: f32,
+    // 起跳后还在上升，可以通过松开跳跃键截断
+    jumping: bool,
+}
+
+impl CharacterController {
+    // 是否站在地面上
+    pub fn is_grounded(&self) -> bool {
+        self.ground_normal.is_some()
+    }
+}
+
+// 使用玩家的碰撞体向下投射，检测脚下的地面，忽略传感器和过陡的斜面
+fn update_grounded(
+    mut player: Query<
+        (
+            Entity,
+            &mut CharacterController,
+            &Collider,
+            &Position,
+            &Rotation,
+            &LinearVelocity,
+        ),
+        With<PlayerRigidBody>,
+    >,
+    sensors: Query<(), With<Sensor>>,
+    velocities: Query<&LinearVelocity, Without<PlayerRigidBody>>,
+    spatial_query: SpatialQuery,
+    settings: Res<ControllerSettings>,
+) {
+    let Ok((entity, mut controller, collider, position, rotation, linear_velocity)) =
+        player.get_single_mut()
+    else {
+        return;
+    };
+    // 起跳后上升的过程中不检测地面，避免刚离地时又被判定为站在地面上
+    if controller.jumping && linear_velocity.y > 0. {
+        controller.ground_normal = None;
+        controller.ground_velocity = Vec2::ZERO;
+        return;
+    }
+    let shape = Collider::from(collider.shape_scaled().clone());
+    let hits = spatial_query.shape_hits(
+        &shape,
+        position.0,
+        rotation.as_radians(),
+        Dir2::NEG_Y,
+        settings.ground_distance,
+        4,
+        true,
+        SpatialQueryFilter::from_excluded_entities([entity]),
+    );
+    let ground = hits
+        .iter()
+        .filter(|hit| !sensors.contains(hit.entity))
+        .find(|hit| hit.normal1.angle_between(Vec2::Y).abs() <= settings.max_slope_angle);
+    controller.ground_normal = ground.map(|hit| hit.normal1);
+    controller.ground_velocity = ground
+        .and_then(|hit| velocities.get(hit.entity).ok())
+        .map_or(Vec2::ZERO, |ground_velocity| ground_velocity.0);
+}
+
+// 玩家移动，沿着地面的切线加速，没有输入时减速，速度相对于站立的地面
+fn player_movement(
+    mut player: Query<
+        (&CharacterController, &mut LinearVelocity),
+        (With<PlayerRigidBody>, Without<PlayerDying>),
+    >,
+    action_input: ActionInput,
+    settings: Res<ControllerSettings>,
+    boosts: Res<Boosts>,
+    time: Res<Time>,
+    mut run_stats: ResMut<RunStats>,
+) {
+    let Ok((controller, mut linear_velocity)) = player.get_single_mut() else {
+        return;
+    };
+    let direction = match (
+        action_input.pressed(Action::MoveLeft),
+        action_input.pressed(Action::MoveRight),
+    ) {
+        (true, false) => -1.,
+        (false, true) => 1.,
+        _ => 0.,
+    };
+    let tangent = controller
+        .ground_normal
+        .map_or(Vec2::X, |normal| Vec2::new(normal.y, -normal.x));
+    let rate = match (controller.is_grounded(), direction != 0.) {
+        (true, true) => settings.ground_acceleration,
+        (true, false) => settings.ground_friction,
+        (false, true) => settings.air_acceleration,
+        (false, false) => settings.air_friction,
+    };
+    let speed = (linear_velocity.0 - controller.ground_velocity).dot(tangent);
+    let target_speed = direction * settings.max_speed * boosts.speed_multiplier();
+    let max_delta = rate * time.delta_seconds();
+    let new_speed = speed + (target_speed - speed).clamp(-max_delta, max_delta);
+    linear_velocity.0 += tangent * (new_speed - speed);
+    if controller.is_grounded() {
+        run_stats.distance += new_speed.abs() * time.delta_seconds();
+    }
+}
+
+// 玩家跳跃，支持土狼时间、跳跃缓冲和可变跳跃高度
+fn player_jump(
+    mut player: Query<
+        (&mut CharacterController, &mut LinearVelocity),
+        (With<PlayerRigidBody>, Without<PlayerDying>),
+    >,
+    action_input: ActionInput,
+    settings: Res<ControllerSettings>,
+    boosts: Res<Boosts>,
+    time: Res<Time>,
+    mut run_stats: ResMut<RunStats>,
+    mut sound_writer: EventWriter<PlaySound>,
+) {
+    let Ok((mut controller, mut linear_velocity)) = player.get_single_mut() else {
+        return;
+    };
+    let delta = time.delta_seconds();
+    if controller.is_grounded() {
+        controller.coyote_left = settings.coyote_time;
+    } else {
+        controller.coyote_left = (controller.coyote_left - delta).max(0.);
+    }
+    if action_input.just_pressed(Action::Jump) {
+        controller.jump_buffer_left = settings.jump_buffer_time;
+    } else {
+        controller.jump_buffer_left = (controller.jump_buffer_left - delta).max(0.);
+    }
+
+    if controller.jump_buffer_left > 0. && controller.coyote_left > 0. {
+        linear_velocity.y = settings.jump_speed * boosts.jump_multiplier();
+        controller.jump_buffer_left = 0.;
+        controller.coyote_left = 0.;
+        controller.jumping = true;
+        run_stats.jumps += 1;
+        sound_writer.send(PlaySound(SoundEffect::Jump));
+        controller.ground_normal = None;
+    } else if controller.jumping {
+        if linear_velocity.y <= 0. {
+            controller.jumping = false;
+        } else if !action_input.pressed(Action::Jump) {
+            linear_velocity.y *= settings.jump_cut;
+            controller.jumping = false;
+        }
+    }
+}
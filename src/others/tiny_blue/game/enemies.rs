@@ -0,0 +1,58 @@
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+use super::{
+    collider::{player_contact, EnemyBody, PlayerRigidBody},
+    controller::ControllerSettings,
+    lives::{PlayerDying, PlayerHit},
+    GamePhase, Score,
+};
+
+// 踩死一个敌人的分数
+const STOMP_SCORE: usize = 20;
+// 接触法线与竖直向下的夹角余弦大于这个值时算作踩在敌人头上
+const STOMP_MIN_COS: f32 = 0.7;
+
+pub fn plugin(app: &mut App) {
+    app.register_type::<Stompable>()
+        .add_systems(Update, contact_enemy.run_if(in_state(GamePhase::Playing)));
+}
+
+// 可以被玩家踩死的敌人，在 Blender 中作为自定义属性添加到敌人上
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct Stompable;
+
+// 接触敌人，从上方踩中可以被踩死的敌人时消灭它并弹起，否则玩家受到伤害
+fn contact_enemy(
+    mut commands: Commands,
+    mut player: Query<
+        (Entity, &Rotation, &mut LinearVelocity, Has<PlayerDying>),
+        With<PlayerRigidBody>,
+    >,
+    enemies: Query<Has<Stompable>, With<EnemyBody>>,
+    mut collision_reader: EventReader<Collision>,
+    mut hit_writer: EventWriter<PlayerHit>,
+    mut score: ResMut<Score>,
+    settings: Res<ControllerSettings>,
+) {
+    let Ok((player_entity, rotation, mut linear_velocity, dying)) = player.get_single_mut() else {
+        return;
+    };
+    for collision in collision_reader.read() {
+        let Some((enemy_entity, normal)) = player_contact(&collision.0, player_entity, rotation)
+        else {
+            continue;
+        };
+        let Ok(stompable) = enemies.get(enemy_entity) else {
+            continue;
+        };
+        if stompable && !dying && normal.dot(Vec2::NEG_Y) > STOMP_MIN_COS {
+            commands.entity(enemy_entity).despawn_recursive();
+            linear_velocity.y = settings.jump_speed;
+            score.0 += STOMP_SCORE;
+        } else {
+            hit_writer.send(PlayerHit);
+        }
+    }
+}
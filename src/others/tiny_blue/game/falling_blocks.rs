@@ -0,0 +1,79 @@
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+use super::{
+    collider::{player_contact, FallingBlockBody, PlayerRigidBody},
+    GamePhase,
+};
+
+// 玩家站上去后开始掉落前的时间
+const FALL_DELAY_SECONDS: f32 = 0.5;
+// 掉落的加速度
+const FALL_ACCELERATION: f32 = 9.81;
+// 掉落多久后删除
+const FALL_SECONDS: f32 = 3.;
+// 接触法线与竖直向下的夹角余弦大于这个值时算作站在方块上
+const STAND_MIN_COS: f32 = 0.7;
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (trigger_falling_block, drop_falling_block).run_if(in_state(GamePhase::Playing)),
+    )
+    .add_systems(OnExit(GamePhase::Playing), stop_falling_blocks);
+}
+
+// 方块被触发后，先等待一段时间，再加速掉落
+#[derive(Component)]
+struct Falling {
+    delay: Timer,
+    fall: Timer,
+}
+
+// 玩家站在掉落方块上时触发掉落
+fn trigger_falling_block(
+    mut commands: Commands,
+    player: Query<(Entity, &Rotation), With<PlayerRigidBody>>,
+    blocks: Query<(), (With<FallingBlockBody>, Without<Falling>)>,
+    mut collision_reader: EventReader<Collision>,
+) {
+    let Ok((player_entity, rotation)) = player.get_single() else {
+        return;
+    };
+    for collision in collision_reader.read() {
+        let Some((block_entity, normal)) = player_contact(&collision.0, player_entity, rotation)
+        else {
+            continue;
+        };
+        if blocks.contains(block_entity) && normal.dot(Vec2::NEG_Y) > STAND_MIN_COS {
+            commands.entity(block_entity).insert(Falling {
+                delay: Timer::from_seconds(FALL_DELAY_SECONDS, TimerMode::Once),
+                fall: Timer::from_seconds(FALL_SECONDS, TimerMode::Once),
+            });
+        }
+    }
+}
+
+// 等待结束后加速掉落，掉落一段时间后删除
+fn drop_falling_block(
+    mut commands: Commands,
+    mut blocks: Query<(Entity, &mut Falling, &mut LinearVelocity)>,
+    time: Res<Time>,
+) {
+    for (entity, mut falling, mut linear_velocity) in &mut blocks {
+        if !falling.delay.tick(time.delta()).finished() {
+            continue;
+        }
+        linear_velocity.y -= FALL_ACCELERATION * time.delta_seconds();
+        if falling.fall.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+// 离开游戏阶段时停止掉落，回来后重新加速
+fn stop_falling_blocks(mut blocks: Query<&mut LinearVelocity, With<Falling>>) {
+    for mut linear_velocity in &mut blocks {
+        linear_velocity.0 = Vec2::ZERO;
+    }
+}
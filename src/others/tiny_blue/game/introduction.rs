@@ -1,14 +1,15 @@
 use bevy::{prelude::*, render::view::NoFrustumCulling};
 
-use crate::{spawn_button, AppDefaultFont};
+use crate::{
+    input::{Action, InputBindings},
+    localization::LocalizedText,
+    spawn_button, AppDefaultFont,
+};
 
-use super::{GamePhase, PlayerAnimation};
-
-// 游戏介绍文本
-const INTRODUCTION_TEXT: &str = "\
-游戏目标：吃掉所有的红色小球
-操作方式：A 向左移动，D 向右移动，J 跳跃
-";
+use super::{
+    animation::{PlayerAnimation, PlayerAnimationMachine},
+    GamePhase,
+};
 
 pub fn plugin(app: &mut App) {
     app.add_systems(
@@ -30,7 +31,21 @@ pub fn plugin(app: &mut App) {
 struct SkipIntroductionButton;
 
 // 生成介绍页面
-fn spawn_introduction_page(mut commands: Commands, default_font: Res<AppDefaultFont>) {
+fn spawn_introduction_page(
+    mut commands: Commands,
+    default_font: Res<AppDefaultFont>,
+    bindings: Res<InputBindings>,
+) {
+    // 游戏目标、操作方式标题和每个动作的按键，每行是一个本地化文本
+    let mut introduction_lines = vec![
+        LocalizedText::key("introduction.goal"),
+        LocalizedText::key("introduction.controls"),
+    ];
+    introduction_lines.extend(Action::ALL.map(|action| {
+        LocalizedText::key("introduction.action")
+            .with_arg("binding", bindings.binding_text(action))
+            .with_key_arg("action", action.label())
+    }));
     commands
         .spawn((
             NodeBundle {
@@ -48,18 +63,23 @@ fn spawn_introduction_page(mut commands: Commands, default_font: Res<AppDefaultF
             StateScoped(GamePhase::Introduction),
         ))
         .with_children(|parent| {
-            parent.spawn(TextBundle::from_section(
-                INTRODUCTION_TEXT,
-                TextStyle {
-                    font: default_font.clone(),
-                    font_size: 60.,
-                    color: Color::WHITE,
-                },
-            ));
+            for line in introduction_lines {
+                parent.spawn((
+                    TextBundle::from_section(
+                        "",
+                        TextStyle {
+                            font: default_font.clone(),
+                            font_size: 60.,
+                            color: Color::WHITE,
+                        },
+                    ),
+                    line,
+                ));
+            }
             spawn_button(
                 parent,
                 SkipIntroductionButton,
-                "跳过介绍",
+                "introduction.skip",
                 default_font.clone(),
                 UiRect::top(Val::Percent(10.)),
             );
@@ -91,7 +111,7 @@ fn player_no_frustum_culling(mut commands: Commands, bodies: Query<(Entity, &Nam
     }
 }
 
-// 为玩家添加动画图和动画过渡
+// 为玩家添加动画图、动画过渡和动画状态机
 fn player_animation_transitions(
     mut commands: Commands,
     player: Query<Entity, With<AnimationPlayer>>,
@@ -100,7 +120,9 @@ fn player_animation_transitions(
     let Ok(entity) = player.get_single() else {
         return;
     };
-    commands
-        .entity(entity)
-        .insert((animation.graph.clone(), AnimationTransitions::new()));
+    commands.entity(entity).insert((
+        animation.graph.clone(),
+        AnimationTransitions::new(),
+        PlayerAnimationMachine::default(),
+    ));
 }
@@ -0,0 +1,109 @@
+use bevy::{color::palettes::tailwind, prelude::*};
+
+use crate::{
+    levels::{BestScores, CurrentLevel, Levels},
+    localization::LocalizedText,
+    spawn_button, AppDefaultFont,
+};
+
+use super::{pressed_return_start_menu_button, GamePhase, ReturnStartMenuButton, Score};
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(GamePhase::LevelComplete), spawn_level_complete_page)
+        .add_systems(
+            Update,
+            (pressed_next_level_button, pressed_return_start_menu_button)
+                .run_if(in_state(GamePhase::LevelComplete)),
+        );
+}
+
+// 下一关按钮
+#[derive(Component)]
+struct NextLevelButton;
+
+// 生成关卡完成页面
+fn spawn_level_complete_page(
+    mut commands: Commands,
+    default_font: Res<AppDefaultFont>,
+    score: Res<Score>,
+    levels: Res<Levels>,
+    current_level: Res<CurrentLevel>,
+    best_scores: Res<BestScores>,
+) {
+    let level = levels.get_level(*current_level);
+    let best_score = best_scores.get(level).unwrap_or(score.0);
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    width: Val::Percent(100.),
+                    height: Val::Percent(100.),
+                    justify_content: JustifyContent::SpaceEvenly,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: BackgroundColor(Color::BLACK.with_alpha(0.5)),
+                ..default()
+            },
+            StateScoped(GamePhase::LevelComplete),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: default_font.clone(),
+                        font_size: 160.,
+                        color: Color::WHITE,
+                    },
+                ),
+                LocalizedText::key("level_complete.title").with_key_arg("level", &level.name),
+            ));
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: default_font.clone(),
+                        font_size: 80.,
+                        color: tailwind::PINK_400.into(),
+                    },
+                ),
+                LocalizedText::key("level_complete.score")
+                    .with_arg("score", score.0)
+                    .with_arg("best_score", best_score),
+            ));
+            spawn_button(
+                parent,
+                NextLevelButton,
+                "level_complete.next",
+                default_font.clone(),
+                UiRect::ZERO,
+            );
+            spawn_button(
+                parent,
+                ReturnStartMenuButton,
+                "common.start_menu",
+                default_font.clone(),
+                UiRect::ZERO,
+            );
+        });
+}
+
+// 按下下一关按钮，重新加载下一关的场景
+fn pressed_next_level_button(
+    button: Query<&Interaction, (Changed<Interaction>, With<NextLevelButton>)>,
+    levels: Res<Levels>,
+    mut current_level: ResMut<CurrentLevel>,
+    mut next_state: ResMut<NextState<GamePhase>>,
+) {
+    let Ok(interaction) = button.get_single() else {
+        return;
+    };
+    if let Interaction::Pressed = interaction {
+        if let Some(next_level) = levels.next(*current_level) {
+            *current_level = next_level;
+            next_state.set(GamePhase::Loading);
+        }
+    }
+}
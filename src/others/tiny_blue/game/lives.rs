@@ -0,0 +1,244 @@
+use avian2d::prelude::*;
+use bevy::{math::Affine3A, prelude::*};
+
+use crate::{run_stats::RunStats, AppState};
+
+use super::{
+    collider::{other_body, CheckpointSensor, PlayerRigidBody},
+    GameOverReason, GamePhase,
+};
+
+// 初始生命数
+const START_LIVES: usize = 3;
+// 死亡动画时长
+const DEATH_SECONDS: f32 = 0.8;
+// 复活后的无敌时长
+const INVULNERABLE_SECONDS: f32 = 1.5;
+// 死亡和无敌时的闪烁间隔
+const BLINK_SECONDS: f32 = 0.1;
+
+pub fn plugin(app: &mut App) {
+    app.add_event::<PlayerHit>()
+        .add_systems(OnEnter(AppState::Game), insert_lives)
+        .add_systems(OnExit(AppState::Game), remove_lives)
+        .add_systems(OnEnter(GamePhase::Loading), reset_checkpoint)
+        .add_systems(
+            Update,
+            (
+                init_checkpoint,
+                reach_checkpoint,
+                (player_hit, blink_player, player_death, player_invulnerable).chain(),
+                update_lives_text,
+            )
+                .run_if(in_state(GamePhase::Playing)),
+        );
+}
+
+// 剩余生命数，整局游戏共用
+#[derive(Resource)]
+pub struct Lives(pub usize);
+
+// 生命文本
+#[derive(Component)]
+pub struct LivesText;
+
+// 玩家复活的位置，在玩家父实体的坐标系中，进入关卡时是玩家的初始位置
+#[derive(Resource, Default)]
+pub struct Checkpoint(pub Option<Vec2>);
+
+// 玩家受到伤害，死亡和无敌时会被忽略
+#[derive(Event)]
+pub struct PlayerHit;
+
+// 玩家正在死亡，不能移动
+#[derive(Component)]
+pub struct PlayerDying(Timer);
+
+// 玩家复活后或拾取无敌后短暂无敌
+#[derive(Component)]
+pub struct Invulnerable(Timer);
+
+impl Invulnerable {
+    pub fn new(seconds: f32) -> Self {
+        Self(Timer::from_seconds(seconds, TimerMode::Once))
+    }
+
+    // 无敌的计时器
+    pub fn timer(&self) -> &Timer {
+        &self.0
+    }
+}
+
+// 插入生命资源
+fn insert_lives(mut commands: Commands) {
+    commands.insert_resource(Lives(START_LIVES));
+}
+
+// 删除生命和检查点资源
+fn remove_lives(mut commands: Commands) {
+    commands.remove_resource::<Lives>();
+    commands.remove_resource::<Checkpoint>();
+}
+
+// 进入新关卡时清除检查点
+fn reset_checkpoint(mut commands: Commands) {
+    commands.insert_resource(Checkpoint::default());
+}
+
+// 使用玩家的初始位置作为第一个检查点
+fn init_checkpoint(
+    mut checkpoint: ResMut<Checkpoint>,
+    player: Query<&Transform, With<PlayerRigidBody>>,
+) {
+    if checkpoint.0.is_some() {
+        return;
+    }
+    let Ok(transform) = player.get_single() else {
+        return;
+    };
+    checkpoint.0 = Some(transform.translation.truncate());
+}
+
+// 玩家经过检查点，检查点的原点就是复活位置
+fn reach_checkpoint(
+    player: Query<(Entity, Option<&Parent>), With<PlayerRigidBody>>,
+    checkpoints: Query<&GlobalTransform, With<CheckpointSensor>>,
+    global_transforms: Query<&GlobalTransform>,
+    mut collision_reader: EventReader<Collision>,
+    mut checkpoint: ResMut<Checkpoint>,
+) {
+    let Ok((player_entity, player_parent)) = player.get_single() else {
+        return;
+    };
+    // 检查点使用玩家父实体坐标系中的位置，与玩家的 Transform 一致
+    let parent_inverse = player_parent
+        .and_then(|parent| global_transforms.get(parent.get()).ok())
+        .map_or(Affine3A::IDENTITY, |parent| parent.affine().inverse());
+    for collision in collision_reader.read() {
+        let Some(checkpoint_entity) = other_body(&collision.0, player_entity) else {
+            continue;
+        };
+        if let Ok(transform) = checkpoints.get(checkpoint_entity) {
+            let position = parent_inverse.transform_point3(transform.translation());
+            checkpoint.0 = Some(position.truncate());
+        }
+    }
+}
+
+// 玩家受到伤害，失去一条生命并开始死亡动画
+fn player_hit(
+    mut commands: Commands,
+    mut hit_reader: EventReader<PlayerHit>,
+    mut player: Query<
+        (Entity, &mut LinearVelocity),
+        (
+            With<PlayerRigidBody>,
+            Without<PlayerDying>,
+            Without<Invulnerable>,
+        ),
+    >,
+    mut lives: ResMut<Lives>,
+    mut run_stats: ResMut<RunStats>,
+) {
+    if hit_reader.is_empty() {
+        return;
+    }
+    hit_reader.clear();
+    let Ok((entity, mut linear_velocity)) = player.get_single_mut() else {
+        return;
+    };
+    lives.0 = lives.0.saturating_sub(1);
+    run_stats.deaths += 1;
+    linear_velocity.0 = Vec2::ZERO;
+    commands.entity(entity).insert((
+        PlayerDying(Timer::from_seconds(DEATH_SECONDS, TimerMode::Once)),
+        GravityScale(0.),
+    ));
+}
+
+// 死亡和无敌时玩家闪烁
+fn blink_player(
+    mut player: Query<
+        (&mut Visibility, Option<&PlayerDying>, Option<&Invulnerable>),
+        With<PlayerRigidBody>,
+    >,
+) {
+    let Ok((mut visibility, dying, invulnerable)) = player.get_single_mut() else {
+        return;
+    };
+    let Some(elapsed) = dying
+        .map(|dying| dying.0.elapsed_secs())
+        .or(invulnerable.map(|invulnerable| invulnerable.0.elapsed_secs()))
+    else {
+        return;
+    };
+    *visibility = if ((elapsed / BLINK_SECONDS) as u32).is_multiple_of(2) {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+}
+
+// 死亡动画结束后，在检查点复活，没有生命时游戏结束
+fn player_death(
+    mut commands: Commands,
+    mut player: Query<(
+        Entity,
+        &mut PlayerDying,
+        &mut Transform,
+        &mut LinearVelocity,
+        &mut Visibility,
+    )>,
+    checkpoint: Res<Checkpoint>,
+    lives: Res<Lives>,
+    time: Res<Time>,
+    mut next_state: ResMut<NextState<GamePhase>>,
+) {
+    let Ok((entity, mut dying, mut transform, mut linear_velocity, mut visibility)) =
+        player.get_single_mut()
+    else {
+        return;
+    };
+    linear_velocity.0 = Vec2::ZERO;
+    if !dying.0.tick(time.delta()).finished() {
+        return;
+    }
+    *visibility = Visibility::Inherited;
+    if lives.0 == 0 {
+        commands.insert_resource(GameOverReason::OutOfLives);
+        next_state.set(GamePhase::Over);
+        return;
+    }
+    if let Some(checkpoint) = checkpoint.0 {
+        transform.translation = checkpoint.extend(transform.translation.z);
+    }
+    commands
+        .entity(entity)
+        .remove::<(PlayerDying, GravityScale)>()
+        .insert(Invulnerable::new(INVULNERABLE_SECONDS));
+}
+
+// 无敌时间结束
+fn player_invulnerable(
+    mut commands: Commands,
+    mut player: Query<(Entity, &mut Invulnerable, &mut Visibility)>,
+    time: Res<Time>,
+) {
+    let Ok((entity, mut invulnerable, mut visibility)) = player.get_single_mut() else {
+        return;
+    };
+    if invulnerable.0.tick(time.delta()).finished() {
+        *visibility = Visibility::Inherited;
+        commands.entity(entity).remove::<Invulnerable>();
+    }
+}
+
+// 更新生命文本
+fn update_lives_text(mut lives_text: Query<&mut Text, With<LivesText>>, lives: Res<Lives>) {
+    if lives.is_changed() {
+        let Ok(mut text) = lives_text.get_single_mut() else {
+            return;
+        };
+        text.sections[1].value = lives.0.to_string();
+    }
+}
@@ -45,14 +45,14 @@ fn spawn_menu_page(mut commands: Commands, default_font: Res<AppDefaultFont>) {
             spawn_button(
                 parent,
                 ReturnGameButton,
-                "返回游戏",
+                "game_menu.resume",
                 default_font.clone(),
                 UiRect::ZERO,
             );
             spawn_button(
                 parent,
                 ReturnStartMenuButton,
-                "开始菜单",
+                "common.start_menu",
                 default_font.clone(),
                 UiRect::top(Val::Percent(10.)),
             );
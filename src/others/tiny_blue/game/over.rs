@@ -1,23 +1,125 @@
-use bevy::{color::palettes::tailwind, prelude::*};
+use std::time::{SystemTime, UNIX_EPOCH};
 
-use crate::{spawn_button, AppDefaultFont};
+use bevy::{
+    color::palettes::tailwind,
+    ecs::system::SystemParam,
+    input::{
+        keyboard::{Key, KeyboardInput},
+        ButtonState,
+    },
+    prelude::*,
+    window::PrimaryWindow,
+};
 
-use super::{pressed_return_start_menu_button, GamePhase, ReturnStartMenuButton, Score};
+use serde::Serialize;
+
+use crate::{
+    high_scores::{format_time, today, HighScore, HighScores},
+    levels::{CurrentLevel, Levels},
+    localization::{Localization, LocalizedText},
+    run_stats::RunStats,
+    spawn_button,
+    storage::save_json,
+    AppDefaultFont,
+};
+
+use super::{
+    controller::ControllerSettings, pressed_return_start_menu_button, GameOverReason, GamePhase,
+    ReturnStartMenuButton, Score,
+};
+
+// 名字的最大字符数
+const MAX_NAME_CHARS: usize = 12;
+// 没有输入名字时使用的名字的本地化键
+const DEFAULT_NAME_KEY: &str = "over.default_name";
+// 每局游戏的记录所在的子目录
+const RUNS_DIR: &str = "runs";
 
 pub fn plugin(app: &mut App) {
-    app.add_systems(OnEnter(GamePhase::Over), spawn_game_over_page)
-        .add_systems(
-            Update,
-            pressed_return_start_menu_button.run_if(in_state(GamePhase::Over)),
-        );
+    app.add_systems(
+        OnEnter(GamePhase::Over),
+        (
+            // 只有成绩可以进入排行榜、生成了名字输入框时才打开输入法
+            (
+                spawn_game_over_page,
+                enable_ime.run_if(any_with_component::<NameInputText>),
+            )
+                .chain(),
+            export_run_record,
+        ),
+    )
+    .add_systems(OnExit(GamePhase::Over), disable_ime)
+    .add_systems(
+        Update,
+        (
+            ime_input,
+            pressed_backspace,
+            pressed_save_high_score_button,
+            pressed_return_start_menu_button,
+        )
+            .run_if(in_state(GamePhase::Over)),
+    );
+}
+
+// 输入名字的区域，保存后替换为名次
+#[derive(Component)]
+struct NameEntry;
+
+// 一局游戏的记录，导出为 JSON，用于比较不同版本的手感调整
+#[derive(Serialize)]
+struct RunRecord<'a> {
+    version: &'static str,
+    date: String,
+    result: &'static str,
+    // 结束时所在的关卡
+    level: &'a str,
+    score: usize,
+    time_bonus: usize,
+    final_score: usize,
+    stats: &'a RunStats,
+    controller: &'a ControllerSettings,
 }
 
-// 生成游戏结束页面
+// 名字输入框的文本，第一段是已经输入的名字，第二段是输入法正在组合的文字
+#[derive(Component)]
+struct NameInputText;
+
+// 保存到排行榜按钮
+#[derive(Component)]
+struct SaveHighScoreButton;
+
+// 生成游戏结束页面，显示统计，成绩能进入排行榜时显示名字输入框
 fn spawn_game_over_page(
     mut commands: Commands,
     default_font: Res<AppDefaultFont>,
     score: Res<Score>,
+    run_stats: Res<RunStats>,
+    reason: Res<GameOverReason>,
+    high_scores: Res<HighScores>,
 ) {
+    let title = match *reason {
+        GameOverReason::AllLevelsCleared => "over.all_levels_cleared",
+        GameOverReason::OutOfLives => "over.out_of_lives",
+    };
+    let qualifies = high_scores.qualifies(&HighScore {
+        name: String::new(),
+        score: run_stats.final_score(score.0),
+        seconds: run_stats.seconds,
+        date: String::new(),
+    });
+    let stats = [
+        ("over.score", run_stats.total_score(score.0).to_string()),
+        ("over.time_bonus", run_stats.time_bonus().to_string()),
+        (
+            "over.final_score",
+            run_stats.final_score(score.0).to_string(),
+        ),
+        ("over.time", format_time(run_stats.seconds)),
+        ("over.cleared_levels", run_stats.cleared_levels.to_string()),
+        ("over.deaths", run_stats.deaths.to_string()),
+        ("over.jumps", run_stats.jumps.to_string()),
+        ("over.distance", format!("{:.1}", run_stats.distance)),
+    ];
     commands
         .spawn((
             NodeBundle {
@@ -35,28 +137,307 @@ fn spawn_game_over_page(
             StateScoped(GamePhase::Over),
         ))
         .with_children(|parent| {
-            parent.spawn(TextBundle::from_section(
-                format!("分数：{}", score.0),
-                TextStyle {
-                    font: default_font.clone(),
-                    font_size: 160.,
-                    color: tailwind::PINK_400.into(),
-                },
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: default_font.clone(),
+                        font_size: 120.,
+                        color: Color::WHITE,
+                    },
+                ),
+                LocalizedText::key(title),
             ));
-            parent.spawn(TextBundle::from_section(
-                "Game Over!",
-                TextStyle {
-                    font: default_font.clone(),
-                    font_size: 200.,
-                    color: Color::WHITE,
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        display: Display::Grid,
+                        grid_template_columns: RepeatedGridTrack::auto(4),
+                        column_gap: Val::Px(40.),
+                        row_gap: Val::Px(10.),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for (label, value) in stats {
+                        parent.spawn((
+                            TextBundle::from_section(
+                                "",
+                                TextStyle {
+                                    font: default_font.clone(),
+                                    font_size: 50.,
+                                    color: Color::WHITE,
+                                },
+                            ),
+                            LocalizedText::key(label),
+                        ));
+                        parent.spawn(TextBundle::from_section(
+                            value,
+                            TextStyle {
+                                font: default_font.clone(),
+                                font_size: 50.,
+                                color: tailwind::PINK_400.into(),
+                            },
+                        ));
+                    }
+                });
+            if qualifies {
+                spawn_name_entry(parent, &default_font);
+            }
+            spawn_button(
+                parent,
+                ReturnStartMenuButton,
+                "common.start_menu",
+                default_font.clone(),
+                UiRect::ZERO,
+            );
+        });
+}
+
+// 导出本局游戏的记录
+fn export_run_record(
+    score: Res<Score>,
+    run_stats: Res<RunStats>,
+    reason: Res<GameOverReason>,
+    levels: Res<Levels>,
+    current_level: Res<CurrentLevel>,
+    controller_settings: Res<ControllerSettings>,
+) {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+    let record = RunRecord {
+        version: env!("CARGO_PKG_VERSION"),
+        date: today(),
+        result: match *reason {
+            GameOverReason::AllLevelsCleared => "cleared",
+            GameOverReason::OutOfLives => "out_of_lives",
+        },
+        level: &levels.get_level(*current_level).name,
+        score: run_stats.total_score(score.0),
+        time_bonus: run_stats.time_bonus(),
+        final_score: run_stats.final_score(score.0),
+        stats: &run_stats,
+        controller: &controller_settings,
+    };
+    save_json(&format!("{RUNS_DIR}/run-{timestamp}.json"), &record);
+}
+
+// 生成名字输入框和保存按钮
+fn spawn_name_entry(parent: &mut ChildBuilder, default_font: &Handle<Font>) {
+    parent
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    align_items: AlignItems::Center,
+                    column_gap: Val::Px(40.),
+                    ..default()
                 },
+                ..default()
+            },
+            NameEntry,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: default_font.clone(),
+                        font_size: 70.,
+                        color: Color::WHITE,
+                    },
+                ),
+                LocalizedText::key("over.new_record"),
             ));
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        justify_content: JustifyContent::Start,
+                        align_items: AlignItems::Center,
+                        border: UiRect::all(Val::Px(10.)),
+                        padding: UiRect::horizontal(Val::Px(20.)),
+                        width: Val::Px(800.),
+                        height: Val::Px(120.),
+                        ..default()
+                    },
+                    background_color: BackgroundColor(Color::WHITE),
+                    border_color: BorderColor(Color::BLACK),
+                    border_radius: BorderRadius::all(Val::Px(40.)),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn((
+                        TextBundle::from_sections([
+                            TextSection::new(
+                                "",
+                                TextStyle {
+                                    font: default_font.clone(),
+                                    font_size: 70.,
+                                    color: Color::BLACK,
+                                },
+                            ),
+                            TextSection::new(
+                                "",
+                                TextStyle {
+                                    font: default_font.clone(),
+                                    font_size: 70.,
+                                    color: Color::Srgba(Srgba::RED),
+                                },
+                            ),
+                        ]),
+                        NameInputText,
+                    ));
+                });
             spawn_button(
                 parent,
-                ReturnStartMenuButton,
-                "开始菜单",
+                SaveHighScoreButton,
+                "over.save",
                 default_font.clone(),
                 UiRect::ZERO,
             );
         });
 }
+
+// 打开输入法，候选框显示在窗口中间
+fn enable_ime(mut window: Query<&mut Window, With<PrimaryWindow>>) {
+    let Ok(mut window) = window.get_single_mut() else {
+        return;
+    };
+    window.ime_enabled = true;
+    window.ime_position = Vec2::new(window.width() / 2., window.height() / 2.);
+}
+
+// 关闭输入法
+fn disable_ime(mut window: Query<&mut Window, With<PrimaryWindow>>) {
+    let Ok(mut window) = window.get_single_mut() else {
+        return;
+    };
+    window.ime_enabled = false;
+}
+
+// 输入法输入，组合中的文字显示为红色
+fn ime_input(
+    mut input_text: Query<&mut Text, With<NameInputText>>,
+    mut ime_reader: EventReader<Ime>,
+) {
+    let Ok(mut text) = input_text.get_single_mut() else {
+        return;
+    };
+    for ime in ime_reader.read() {
+        match ime {
+            Ime::Preedit {
+                window: _,
+                value,
+                cursor: _,
+            } => {
+                text.sections[1].value = value.into();
+            }
+            Ime::Commit { window: _, value } => {
+                let name = &mut text.sections[0].value;
+                let remaining = MAX_NAME_CHARS.saturating_sub(name.chars().count());
+                name.extend(value.chars().take(remaining));
+                text.sections[1].value.clear();
+            }
+            _ => (),
+        }
+    }
+}
+
+// 退格删除最后一个字符，输入法正在组合时由输入法处理
+fn pressed_backspace(
+    mut input_text: Query<&mut Text, With<NameInputText>>,
+    mut keyboard_reader: EventReader<KeyboardInput>,
+) {
+    let Ok(mut text) = input_text.get_single_mut() else {
+        return;
+    };
+    for KeyboardInput {
+        key_code: _,
+        logical_key,
+        state,
+        window: _,
+    } in keyboard_reader.read()
+    {
+        if *logical_key == Key::Backspace
+            && *state == ButtonState::Pressed
+            && text.sections[1].value.is_empty()
+        {
+            text.sections[0].value.pop();
+        }
+    }
+}
+
+// 本局的成绩和保存成绩用到的排行榜
+#[derive(SystemParam)]
+struct HighScoreEntry<'w> {
+    high_scores: ResMut<'w, HighScores>,
+    score: Res<'w, Score>,
+    run_stats: Res<'w, RunStats>,
+    localization: Res<'w, Localization>,
+}
+
+impl HighScoreEntry<'_> {
+    // 用输入的名字保存成绩，返回名次
+    fn save(&mut self, name: &str) -> Option<usize> {
+        let rank = self.high_scores.insert(HighScore {
+            name: if name.is_empty() {
+                self.localization.get(DEFAULT_NAME_KEY)
+            } else {
+                name
+            }
+            .into(),
+            score: self.run_stats.final_score(self.score.0),
+            seconds: self.run_stats.seconds,
+            date: today(),
+        });
+        self.high_scores.save();
+        rank
+    }
+}
+
+// 按下保存按钮，将成绩写入排行榜，输入框替换为名次
+fn pressed_save_high_score_button(
+    mut commands: Commands,
+    button: Query<&Interaction, (Changed<Interaction>, With<SaveHighScoreButton>)>,
+    input_text: Query<&Text, With<NameInputText>>,
+    name_entry: Query<Entity, With<NameEntry>>,
+    mut high_score_entry: HighScoreEntry,
+    default_font: Res<AppDefaultFont>,
+    mut window: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    let Ok(interaction) = button.get_single() else {
+        return;
+    };
+    let Interaction::Pressed = interaction else {
+        return;
+    };
+    let (Ok(text), Ok(name_entry)) = (input_text.get_single(), name_entry.get_single()) else {
+        return;
+    };
+    let rank = high_score_entry.save(text.sections[0].value.trim());
+    // 名字已经保存，关闭输入法
+    if let Ok(mut window) = window.get_single_mut() {
+        window.ime_enabled = false;
+    }
+    let message = rank.map_or_else(
+        || LocalizedText::key("over.not_ranked"),
+        |rank| LocalizedText::key("over.ranked").with_arg("rank", rank + 1),
+    );
+    commands
+        .entity(name_entry)
+        .despawn_descendants()
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: default_font.clone(),
+                        font_size: 70.,
+                        color: Color::WHITE,
+                    },
+                ),
+                message,
+            ));
+        });
+}
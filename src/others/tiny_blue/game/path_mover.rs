@@ -0,0 +1,96 @@
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+use super::GamePhase;
+
+pub fn plugin(app: &mut App) {
+    app.register_type::<PathMover>()
+        .add_systems(
+            Update,
+            (init_path_progress, move_along_path)
+                .chain()
+                .run_if(in_state(GamePhase::Playing)),
+        )
+        .add_systems(OnExit(GamePhase::Playing), stop_path_movers);
+}
+
+// 沿路径点往返移动的运动学刚体，在 Blender 中作为自定义属性添加到显示的物体上
+// points 是相对于初始位置的偏移，初始位置是第一个路径点
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+pub struct PathMover {
+    pub points: Vec<Vec2>,
+    pub speed: f32,
+}
+
+// 路径的世界坐标和当前的目标路径点
+#[derive(Component)]
+struct PathProgress {
+    points: Vec<Vec2>,
+    target: usize,
+    forward: bool,
+}
+
+impl PathProgress {
+    // 到达目标后切换到下一个路径点，到达两端时反向
+    fn advance(&mut self) {
+        if self.points.len() < 2 {
+            return;
+        }
+        if self.forward && self.target + 1 == self.points.len() {
+            self.forward = false;
+        } else if !self.forward && self.target == 0 {
+            self.forward = true;
+        }
+        if self.forward {
+            self.target += 1;
+        } else {
+            self.target -= 1;
+        }
+    }
+}
+
+// 根据刚体的初始位置计算路径
+fn init_path_progress(
+    mut commands: Commands,
+    movers: Query<(Entity, &PathMover, &Position), Without<PathProgress>>,
+) {
+    for (entity, path_mover, position) in &movers {
+        let points = std::iter::once(position.0)
+            .chain(path_mover.points.iter().map(|&point| position.0 + point))
+            .collect();
+        commands.entity(entity).insert(PathProgress {
+            points,
+            target: 0,
+            forward: true,
+        });
+    }
+}
+
+// 设置朝向目标路径点的速度，快到达时切换目标
+fn move_along_path(
+    mut movers: Query<(
+        &PathMover,
+        &mut PathProgress,
+        &Position,
+        &mut LinearVelocity,
+    )>,
+    time: Res<Time>,
+) {
+    let delta_seconds = time.delta_seconds();
+    for (path_mover, mut progress, position, mut linear_velocity) in &mut movers {
+        let to_target = progress.points[progress.target] - position.0;
+        if to_target.length() <= path_mover.speed * delta_seconds {
+            progress.advance();
+        }
+        let to_target = progress.points[progress.target] - position.0;
+        linear_velocity.0 = to_target.normalize_or_zero() * path_mover.speed;
+    }
+}
+
+// 离开游戏阶段时停止移动，回来后继续
+fn stop_path_movers(mut movers: Query<&mut LinearVelocity, With<PathProgress>>) {
+    for mut linear_velocity in &mut movers {
+        linear_velocity.0 = Vec2::ZERO;
+    }
+}
@@ -0,0 +1,296 @@
+use avian2d::prelude::*;
+use bevy::{color::palettes::tailwind, prelude::*};
+
+use crate::{localization::LocalizedText, AppDefaultFont, AppState};
+
+use super::{
+    collider::{other_body, DoorBody, PlayerRigidBody},
+    lives::{Invulnerable, Lives},
+    GamePhase,
+};
+
+// 加速和跳跃强化的时长
+const BOOST_SECONDS: f32 = 8.;
+// 拾取无敌的时长
+const INVULNERABILITY_SECONDS: f32 = 6.;
+// 加速时最大速度的倍数
+const SPEED_BOOST: f32 = 1.5;
+// 跳跃强化时起跳速度的倍数
+const JUMP_BOOST: f32 = 1.3;
+
+pub fn plugin(app: &mut App) {
+    app.register_type::<Pickup>()
+        .add_event::<PickupCollected>()
+        .add_systems(OnEnter(GamePhase::Loading), insert_boosts_and_keys)
+        .add_systems(OnExit(AppState::Game), remove_boosts_and_keys)
+        .add_systems(OnEnter(GamePhase::Playing), spawn_effect_indicators)
+        // 最后一个拾取物可能在完成关卡的同一帧被拾取，整局游戏中都要处理
+        .add_systems(Update, apply_pickup.run_if(in_state(AppState::Game)))
+        .add_systems(
+            Update,
+            (tick_boosts, open_door, update_effect_indicators).run_if(in_state(GamePhase::Playing)),
+        );
+}
+
+// 拾取物的种类和是否需要拾取才能完成关卡，在 Blender 中作为自定义属性添加到食物上
+// 没有这个组件的食物是必须拾取的 Score(FOOD_SCORE)
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[reflect(Component)]
+pub struct Pickup {
+    pub kind: PickupKind,
+    pub required: bool,
+}
+
+// 拾取物的种类
+#[derive(Reflect, Debug, Clone, Copy, PartialEq)]
+pub enum PickupKind {
+    Score(usize),    // 增加分数
+    ExtraLife,       // 增加一条生命
+    SpeedBoost,      // 暂时加速
+    JumpBoost,       // 暂时跳得更高
+    Invulnerability, // 暂时无敌
+    Key,             // 打开一扇门
+}
+
+// 玩家拾取了拾取物，分数在拾取时直接增加，其余效果在这个事件中处理
+#[derive(Event)]
+pub struct PickupCollected(pub PickupKind);
+
+// 加速和跳跃强化的剩余时间
+#[derive(Resource, Default)]
+pub struct Boosts {
+    speed: Option<Timer>,
+    jump: Option<Timer>,
+}
+
+impl Boosts {
+    // 最大速度的倍数
+    pub fn speed_multiplier(&self) -> f32 {
+        if self.speed.is_some() {
+            SPEED_BOOST
+        } else {
+            1.
+        }
+    }
+
+    // 起跳速度的倍数
+    pub fn jump_multiplier(&self) -> f32 {
+        if self.jump.is_some() {
+            JUMP_BOOST
+        } else {
+            1.
+        }
+    }
+}
+
+// 当前关卡持有的钥匙数和已经打开的门的名字
+#[derive(Resource, Default)]
+pub struct KeyRing {
+    pub keys: usize,
+    pub opened_doors: Vec<String>,
+}
+
+// 效果指示器
+#[derive(Component, Debug, Clone, Copy)]
+enum EffectIndicator {
+    SpeedBoost,
+    JumpBoost,
+    Invulnerability,
+    Key,
+}
+
+impl EffectIndicator {
+    const ALL: [Self; 4] = [
+        Self::SpeedBoost,
+        Self::JumpBoost,
+        Self::Invulnerability,
+        Self::Key,
+    ];
+
+    // 指示器名字的本地化键
+    fn label(self) -> &'static str {
+        match self {
+            Self::SpeedBoost => "effect.speed_boost",
+            Self::JumpBoost => "effect.jump_boost",
+            Self::Invulnerability => "effect.invulnerability",
+            Self::Key => "effect.key",
+        }
+    }
+
+    fn color(self) -> Srgba {
+        match self {
+            Self::SpeedBoost => tailwind::AMBER_500,
+            Self::JumpBoost => tailwind::LIME_500,
+            Self::Invulnerability => tailwind::VIOLET_500,
+            Self::Key => tailwind::YELLOW_500,
+        }
+    }
+}
+
+// 每个关卡重新开始计算强化效果和钥匙
+fn insert_boosts_and_keys(mut commands: Commands) {
+    commands.insert_resource(Boosts::default());
+    commands.insert_resource(KeyRing::default());
+}
+
+// 删除强化效果和钥匙资源
+fn remove_boosts_and_keys(mut commands: Commands) {
+    commands.remove_resource::<Boosts>();
+    commands.remove_resource::<KeyRing>();
+}
+
+// 处理拾取物的效果
+fn apply_pickup(
+    mut commands: Commands,
+    mut pickup_reader: EventReader<PickupCollected>,
+    player: Query<Entity, With<PlayerRigidBody>>,
+    mut lives: ResMut<Lives>,
+    mut boosts: ResMut<Boosts>,
+    mut key_ring: ResMut<KeyRing>,
+) {
+    for PickupCollected(kind) in pickup_reader.read() {
+        match kind {
+            PickupKind::Score(_) => (),
+            PickupKind::ExtraLife => lives.0 += 1,
+            PickupKind::SpeedBoost => {
+                boosts.speed = Some(Timer::from_seconds(BOOST_SECONDS, TimerMode::Once));
+            }
+            PickupKind::JumpBoost => {
+                boosts.jump = Some(Timer::from_seconds(BOOST_SECONDS, TimerMode::Once));
+            }
+            PickupKind::Invulnerability => {
+                if let Ok(entity) = player.get_single() {
+                    commands
+                        .entity(entity)
+                        .insert(Invulnerable::new(INVULNERABILITY_SECONDS));
+                }
+            }
+            PickupKind::Key => key_ring.keys += 1,
+        }
+    }
+}
+
+// 强化效果计时，结束后失效
+fn tick_boosts(mut boosts: ResMut<Boosts>, time: Res<Time>) {
+    let boosts = boosts.as_mut();
+    for boost in [&mut boosts.speed, &mut boosts.jump] {
+        if boost
+            .as_mut()
+            .is_some_and(|timer| timer.tick(time.delta()).finished())
+        {
+            *boost = None;
+        }
+    }
+}
+
+// 玩家带着钥匙碰到门时，消耗一把钥匙打开门
+fn open_door(
+    mut commands: Commands,
+    player: Query<Entity, With<PlayerRigidBody>>,
+    doors: Query<Option<&Name>, With<DoorBody>>,
+    mut collision_reader: EventReader<Collision>,
+    mut key_ring: ResMut<KeyRing>,
+) {
+    let Ok(player_entity) = player.get_single() else {
+        return;
+    };
+    for collision in collision_reader.read() {
+        let Some(door_entity) = other_body(&collision.0, player_entity) else {
+            continue;
+        };
+        let Ok(name) = doors.get(door_entity) else {
+            continue;
+        };
+        if key_ring.keys == 0 {
+            continue;
+        }
+        key_ring.keys -= 1;
+        if let Some(name) = name {
+            key_ring.opened_doors.push(name.to_string());
+        }
+        commands.entity(door_entity).despawn_recursive();
+    }
+}
+
+// 在左下角生成效果指示器，没有效果时隐藏
+fn spawn_effect_indicators(mut commands: Commands, default_font: Res<AppDefaultFont>) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(20.),
+                    bottom: Val::Px(20.),
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(10.),
+                    ..default()
+                },
+                ..default()
+            },
+            StateScoped(GamePhase::Playing),
+        ))
+        .with_children(|parent| {
+            let text_style = TextStyle {
+                font: default_font.clone(),
+                font_size: 50.,
+                color: Color::WHITE,
+            };
+            for indicator in EffectIndicator::ALL {
+                parent.spawn((
+                    TextBundle {
+                        text: Text::from_sections([
+                            TextSection::new("", text_style.clone()),
+                            TextSection::new("", text_style.clone()),
+                        ]),
+                        style: Style {
+                            display: Display::None,
+                            padding: UiRect::axes(Val::Px(15.), Val::Px(5.)),
+                            ..default()
+                        },
+                        background_color: BackgroundColor(indicator.color().with_alpha(0.6).into()),
+                        ..default()
+                    },
+                    LocalizedText::key(indicator.label()),
+                    indicator,
+                ));
+            }
+        });
+}
+
+// 更新效果指示器，第一段是本地化的名字，第二段显示剩余时间或钥匙数量
+fn update_effect_indicators(
+    mut indicators: Query<(&EffectIndicator, &mut Text, &mut Style)>,
+    player: Query<Option<&Invulnerable>, With<PlayerRigidBody>>,
+    boosts: Res<Boosts>,
+    key_ring: Res<KeyRing>,
+) {
+    let remaining = |timer: Option<&Timer>| timer.map(Timer::remaining_secs);
+    for (&indicator, mut text, mut style) in &mut indicators {
+        let value = match indicator {
+            EffectIndicator::SpeedBoost => {
+                remaining(boosts.speed.as_ref()).map(|secs| format!("{secs:.1}s"))
+            }
+            EffectIndicator::JumpBoost => {
+                remaining(boosts.jump.as_ref()).map(|secs| format!("{secs:.1}s"))
+            }
+            EffectIndicator::Invulnerability => {
+                remaining(player.get_single().ok().flatten().map(Invulnerable::timer))
+                    .map(|secs| format!("{secs:.1}s"))
+            }
+            EffectIndicator::Key => (key_ring.keys > 0).then(|| format!("×{}", key_ring.keys)),
+        };
+        let display = match value {
+            Some(value) => {
+                let value = format!(" {value}");
+                if text.sections[1].value != value {
+                    text.sections[1].value = value;
+                }
+                Display::Flex
+            }
+            None => Display::None,
+        };
+        if style.display != display {
+            style.display = display;
+        }
+    }
+}
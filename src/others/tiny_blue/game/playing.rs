@@ -1,40 +1,45 @@
-use std::time::Duration;
+use avian2d::prelude::Collision;
+use bevy::{color::palettes::tailwind, ecs::system::SystemParam, prelude::*};
 
-use avian2d::prelude::{Collision, LinearVelocity};
-use bevy::{color::palettes::tailwind, prelude::*};
-
-use crate::AppDefaultFont;
+use crate::{
+    high_scores::format_time,
+    levels::{BestScores, CurrentLevel, Levels},
+    localization::LocalizedText,
+    run_stats::RunStats,
+    AppDefaultFont,
+};
 
 use super::{
-    collider::{FirePitSensor, FoodSensor, PlayerRigidBody, SpikeSensor},
-    open_close_menu_page, GamePhase, PlayerAnimation, Score, ScoreText, FOOD_SCORE,
+    collider::{other_body, FirePitSensor, FoodSensor, PlayerRigidBody, SpikeSensor},
+    lives::{Lives, LivesText, PlayerHit},
+    open_close_menu_page,
+    pickups::{Pickup, PickupCollected, PickupKind},
+    CollectedFoods, GameOverReason, GamePhase, Score, ScoreText, FOOD_SCORE,
 };
 
-// 移动速度
-const MOVEMENT_SPEED: f32 = 3.;
-// 跳跃速度
-const JUMP_SPEED: f32 = 4.;
-
 pub fn plugin(app: &mut App) {
     app.add_systems(OnEnter(GamePhase::Playing), spawn_score_text)
         .add_systems(
             Update,
             (
                 update_score_text,
-                player_movement,
-                player_jump,
+                update_timer_text,
                 player_eat_food,
                 contact_fire_pit_or_spike,
-                game_over,
-                control_walk_animation,
+                level_complete,
                 open_close_menu_page,
             )
                 .run_if(in_state(GamePhase::Playing)),
         );
 }
 
-// 生成分数文本
-fn spawn_score_text(mut commands: Commands, default_font: Res<AppDefaultFont>) {
+// 生成分数、生命和用时文本
+fn spawn_score_text(
+    mut commands: Commands,
+    default_font: Res<AppDefaultFont>,
+    lives: Res<Lives>,
+    run_stats: Res<RunStats>,
+) {
     commands
         .spawn((
             NodeBundle {
@@ -43,6 +48,7 @@ fn spawn_score_text(mut commands: Commands, default_font: Res<AppDefaultFont>) {
                     height: Val::Percent(100.),
                     justify_content: JustifyContent::Center,
                     align_items: AlignItems::FlexStart,
+                    column_gap: Val::Px(20.),
                     ..default()
                 },
                 ..default()
@@ -50,41 +56,81 @@ fn spawn_score_text(mut commands: Commands, default_font: Res<AppDefaultFont>) {
             StateScoped(GamePhase::Playing),
         ))
         .with_children(|parent| {
-            parent
-                .spawn(NodeBundle {
-                    style: Style {
-                        width: Val::Px(300.),
-                        height: Val::Px(100.),
-                        justify_content: JustifyContent::Center,
-                        align_items: AlignItems::Center,
-                        ..default()
+            spawn_hud_text(
+                parent,
+                ScoreText,
+                "hud.score",
+                "0".into(),
+                tailwind::SKY_500,
+                &default_font,
+            );
+            spawn_hud_text(
+                parent,
+                LivesText,
+                "hud.lives",
+                lives.0.to_string(),
+                tailwind::PINK_500,
+                &default_font,
+            );
+            spawn_hud_text(
+                parent,
+                TimerText,
+                "hud.time",
+                format_time(run_stats.seconds),
+                tailwind::EMERALD_500,
+                &default_font,
+            );
+        });
+}
+
+// 生成一个 HUD 文本，第一段是本地化的标签，第二段是会更新的值
+fn spawn_hud_text<T: Component>(
+    parent: &mut ChildBuilder,
+    marker: T,
+    label_key: &str,
+    value: String,
+    color: Srgba,
+    default_font: &Handle<Font>,
+) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Px(300.),
+                height: Val::Px(100.),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            background_color: BackgroundColor(color.with_alpha(0.4).into()),
+            ..default()
+        })
+        .with_children(|parent| {
+            let text_style = TextStyle {
+                font: default_font.clone(),
+                font_size: 80.,
+                color: Color::WHITE,
+            };
+            parent.spawn((
+                TextBundle::from_sections([
+                    TextSection {
+                        value: String::new(),
+                        style: text_style.clone(),
                     },
-                    background_color: BackgroundColor(tailwind::SKY_500.with_alpha(0.4).into()),
-                    ..default()
-                })
-                .with_children(|parent| {
-                    let text_style = TextStyle {
-                        font: default_font.clone(),
-                        font_size: 80.,
-                        color: Color::WHITE,
-                    };
-                    parent.spawn((
-                        TextBundle::from_sections([
-                            TextSection {
-                                value: "分数：".into(),
-                                style: text_style.clone(),
-                            },
-                            TextSection {
-                                value: "0".into(),
-                                style: text_style,
-                            },
-                        ]),
-                        ScoreText,
-                    ));
-                });
+                    TextSection {
+                        value,
+                        style: text_style,
+                    },
+                ]),
+                LocalizedText::key(label_key),
+                marker,
+            ));
         });
 }
 
+// 用时文本
+#[derive(Component)]
+struct TimerText;
+
 // 更新分数文本
 fn update_score_text(mut score_text: Query<&mut Text, With<ScoreText>>, score: Res<Score>) {
     if score.is_changed() {
@@ -95,121 +141,108 @@ fn update_score_text(mut score_text: Query<&mut Text, With<ScoreText>>, score: R
     }
 }
 
-// 玩家移动
-fn player_movement(
-    mut player: Query<&mut LinearVelocity, With<PlayerRigidBody>>,
-    keyboard: Res<ButtonInput<KeyCode>>,
-) {
-    let speed = if keyboard.pressed(KeyCode::KeyA) {
-        -MOVEMENT_SPEED
-    } else if keyboard.pressed(KeyCode::KeyD) {
-        MOVEMENT_SPEED
-    } else {
-        0.
-    };
-    let Ok(mut linear_velocity) = player.get_single_mut() else {
+// 更新用时文本，只在秒数变化时修改
+fn update_timer_text(mut timer_text: Query<&mut Text, With<TimerText>>, run_stats: Res<RunStats>) {
+    let Ok(mut text) = timer_text.get_single_mut() else {
         return;
     };
-    linear_velocity.0.x = speed;
-}
-
-// 玩家跳跃
-fn player_jump(
-    mut player: Query<&mut LinearVelocity, With<PlayerRigidBody>>,
-    keyboard: Res<ButtonInput<KeyCode>>,
-) {
-    let mut speed = 0.;
-    if keyboard.just_pressed(KeyCode::KeyJ) {
-        speed += JUMP_SPEED;
+    let value = format_time(run_stats.seconds);
+    if text.sections[1].value != value {
+        text.sections[1].value = value;
     }
-    let Ok(mut linear_velocity) = player.get_single_mut() else {
-        return;
-    };
-    linear_velocity.0.y = (speed + linear_velocity.0.y).clamp(-JUMP_SPEED, JUMP_SPEED);
 }
 
-// 玩家吃食物
+// 玩家吃食物，没有 Pickup 组件的食物按普通食物计分
 fn player_eat_food(
     mut commands: Commands,
     player: Query<Entity, With<PlayerRigidBody>>,
-    foods: Query<(), With<FoodSensor>>,
+    foods: Query<(Option<&Name>, Option<&Pickup>), With<FoodSensor>>,
     mut collision_reader: EventReader<Collision>,
+    mut pickup_writer: EventWriter<PickupCollected>,
     mut score: ResMut<Score>,
+    mut collected_foods: ResMut<CollectedFoods>,
 ) {
     let Ok(player_entity) = player.get_single() else {
         return;
     };
     for collision in collision_reader.read() {
         if collision.0.is_sensor {
-            let food_entity = if collision.0.entity1 == player_entity {
-                collision.0.entity2
-            } else if collision.0.entity2 == player_entity {
-                collision.0.entity1
-            } else {
-                Entity::PLACEHOLDER
+            let Some(food_entity) = other_body(&collision.0, player_entity) else {
+                continue;
             };
-            if food_entity != Entity::PLACEHOLDER && foods.contains(food_entity) {
-                score.0 += FOOD_SCORE;
+            if let Ok((name, pickup)) = foods.get(food_entity) {
+                let kind = pickup.map_or(PickupKind::Score(FOOD_SCORE), |pickup| pickup.kind);
+                if let PickupKind::Score(value) = kind {
+                    score.0 += value;
+                }
+                pickup_writer.send(PickupCollected(kind));
+                if let Some(name) = name {
+                    collected_foods.0.push(name.to_string());
+                }
                 commands.entity(food_entity).despawn_recursive();
             }
         }
     }
 }
 
-// 接触火坑或尖刺
+// 接触火坑或尖刺，玩家受到伤害
 fn contact_fire_pit_or_spike(
-    mut player: Query<(Entity, &mut Transform, &mut LinearVelocity), With<PlayerRigidBody>>,
+    player: Query<Entity, With<PlayerRigidBody>>,
     fire_pits: Query<(), With<FirePitSensor>>,
     spikes: Query<(), With<SpikeSensor>>,
     mut collision_reader: EventReader<Collision>,
+    mut hit_writer: EventWriter<PlayerHit>,
 ) {
-    let Ok((player_entity, mut player_transform, mut linear_velocity)) = player.get_single_mut()
-    else {
+    let Ok(player_entity) = player.get_single() else {
         return;
     };
     for collision in collision_reader.read() {
-        let fire_pit_or_spike_entity = if collision.0.entity1 == player_entity {
-            collision.0.entity2
-        } else if collision.0.entity2 == player_entity {
-            collision.0.entity1
-        } else {
-            Entity::PLACEHOLDER
+        let Some(fire_pit_or_spike_entity) = other_body(&collision.0, player_entity) else {
+            continue;
         };
-        if fire_pit_or_spike_entity != Entity::PLACEHOLDER
-            && (fire_pits.contains(fire_pit_or_spike_entity)
-                || spikes.contains(fire_pit_or_spike_entity))
+        if fire_pits.contains(fire_pit_or_spike_entity) || spikes.contains(fire_pit_or_spike_entity)
         {
-            player_transform.translation = Vec3::new(-18.837, 0.1, 0.);
-            linear_velocity.0 = Vec2::ZERO;
+            hit_writer.send(PlayerHit);
         }
     }
 }
 
-// 游戏结束
-fn game_over(foods: Query<(), With<FoodSensor>>, mut next_state: ResMut<NextState<GamePhase>>) {
-    if foods.iter().len() == 0 {
-        next_state.set(GamePhase::Over);
+// 当前关卡和本关的成绩
+#[derive(SystemParam)]
+struct LevelResult<'w> {
+    levels: Res<'w, Levels>,
+    current_level: Res<'w, CurrentLevel>,
+    score: Res<'w, Score>,
+    best_scores: ResMut<'w, BestScores>,
+    run_stats: ResMut<'w, RunStats>,
+}
+
+impl LevelResult<'_> {
+    // 记录本关的最高分，返回是否还有下一关
+    fn record(&mut self) -> bool {
+        self.best_scores
+            .record(self.levels.get_level(*self.current_level), self.score.0);
+        self.run_stats.cleared_levels += 1;
+        self.levels.next(*self.current_level).is_some()
     }
 }
 
-// 控制行走动画
-fn control_walk_animation(
-    mut player: Query<(&mut AnimationTransitions, &mut AnimationPlayer), With<PlayerRigidBody>>,
-    animation: Res<PlayerAnimation>,
-    keyboard: Res<ButtonInput<KeyCode>>,
+// 吃掉所有必须拾取的食物后完成关卡，记录最高分，最后一关完成后游戏结束
+fn level_complete(
+    mut commands: Commands,
+    foods: Query<Option<&Pickup>, With<FoodSensor>>,
+    mut level_result: LevelResult,
+    mut next_state: ResMut<NextState<GamePhase>>,
 ) {
-    let Ok((mut animation_transitions, mut animation_palyer)) = player.get_single_mut() else {
-        return;
-    };
-    if keyboard.pressed(KeyCode::KeyA) || keyboard.pressed(KeyCode::KeyD) {
-        if animation_palyer.all_finished() {
-            animation_transitions.play(
-                &mut animation_palyer,
-                animation.animation,
-                Duration::from_millis(300),
-            );
+    if foods
+        .iter()
+        .all(|pickup| pickup.is_some_and(|pickup| !pickup.required))
+    {
+        if level_result.record() {
+            next_state.set(GamePhase::LevelComplete);
+        } else {
+            commands.insert_resource(GameOverReason::AllLevelsCleared);
+            next_state.set(GamePhase::Over);
         }
-    } else if !animation_palyer.all_finished() {
-        animation_palyer.stop(animation.animation);
     }
 }
@@ -0,0 +1,83 @@
+use bevy::prelude::*;
+
+use crate::{
+    audio::{play_music, MusicTrack, PlaySound, SoundEffect},
+    AppState,
+};
+
+use super::{
+    collider::PlayerRigidBody, controller::CharacterController, lives::PlayerDying,
+    pickups::PickupCollected, GameOverReason, GamePhase,
+};
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(
+        OnEnter(GamePhase::Introduction),
+        play_music(MusicTrack::Game),
+    )
+    .add_systems(
+        OnEnter(GamePhase::Over),
+        (play_music(MusicTrack::Over), all_levels_cleared_sound),
+    )
+    .add_systems(OnEnter(GamePhase::LevelComplete), level_complete_sound)
+    .add_systems(
+        Update,
+        (
+            // 最后一个食物可能在完成关卡的同一帧被拾取
+            eat_sound.run_if(in_state(AppState::Game)),
+            (land_sound, death_sound).run_if(in_state(GamePhase::Playing)),
+        ),
+    );
+}
+
+// 拾取食物的音效
+fn eat_sound(
+    mut pickup_reader: EventReader<PickupCollected>,
+    mut sound_writer: EventWriter<PlaySound>,
+) {
+    for _ in pickup_reader.read() {
+        sound_writer.send(PlaySound(SoundEffect::Eat));
+    }
+}
+
+// 落地的音效，刚进入关卡时不播放
+fn land_sound(
+    player: Query<&CharacterController, With<PlayerRigidBody>>,
+    mut was_grounded: Local<Option<bool>>,
+    mut sound_writer: EventWriter<PlaySound>,
+) {
+    let Ok(controller) = player.get_single() else {
+        *was_grounded = None;
+        return;
+    };
+    let grounded = controller.is_grounded();
+    if *was_grounded == Some(false) && grounded {
+        sound_writer.send(PlaySound(SoundEffect::Land));
+    }
+    *was_grounded = Some(grounded);
+}
+
+// 玩家开始死亡的音效
+fn death_sound(
+    dying: Query<(), (With<PlayerRigidBody>, Added<PlayerDying>)>,
+    mut sound_writer: EventWriter<PlaySound>,
+) {
+    if !dying.is_empty() {
+        sound_writer.send(PlaySound(SoundEffect::Death));
+    }
+}
+
+// 完成关卡的音效
+fn level_complete_sound(mut sound_writer: EventWriter<PlaySound>) {
+    sound_writer.send(PlaySound(SoundEffect::LevelComplete));
+}
+
+// 全部通关时也播放完成关卡的音效
+fn all_levels_cleared_sound(
+    reason: Option<Res<GameOverReason>>,
+    mut sound_writer: EventWriter<PlaySound>,
+) {
+    if reason.is_some_and(|reason| *reason == GameOverReason::AllLevelsCleared) {
+        sound_writer.send(PlaySound(SoundEffect::LevelComplete));
+    }
+}
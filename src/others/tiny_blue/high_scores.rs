@@ -0,0 +1,147 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::storage::{load_ron, save_ron};
+
+// 排行榜，位于本地存档目录
+const HIGH_SCORES_FILE: &str = "high_scores.ron";
+// 排行榜的最大条数
+const MAX_HIGH_SCORES: usize = 10;
+
+pub fn plugin(app: &mut App) {
+    app.init_resource::<HighScores>();
+}
+
+// 排行榜中的一条记录
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HighScore {
+    pub name: String,
+    pub score: usize,
+    // 游戏用时，单位是秒
+    pub seconds: f32,
+    // 记录的日期，格式是 YYYY-MM-DD
+    pub date: String,
+}
+
+impl HighScore {
+    // 分数更高的排在前面，分数相同时用时更短的排在前面
+    fn is_better_than(&self, other: &HighScore) -> bool {
+        self.score > other.score || (self.score == other.score && self.seconds < other.seconds)
+    }
+}
+
+// 分数最高的 10 条记录，从高到低排列
+#[derive(Resource, Debug, Deref)]
+pub struct HighScores(Vec<HighScore>);
+
+impl FromWorld for HighScores {
+    fn from_world(_world: &mut World) -> Self {
+        Self(load_ron(HIGH_SCORES_FILE))
+    }
+}
+
+impl HighScores {
+    // 这个成绩能否进入排行榜
+    pub fn qualifies(&self, high_score: &HighScore) -> bool {
+        self.0.len() < MAX_HIGH_SCORES
+            || self
+                .0
+                .last()
+                .is_some_and(|last| high_score.is_better_than(last))
+    }
+
+    // 插入一条记录，返回它的名次，从 0 开始，没有进入排行榜时返回 None
+    pub fn insert(&mut self, high_score: HighScore) -> Option<usize> {
+        let index = self
+            .0
+            .iter()
+            .position(|other| high_score.is_better_than(other))
+            .unwrap_or(self.0.len());
+        if index >= MAX_HIGH_SCORES {
+            return None;
+        }
+        self.0.insert(index, high_score);
+        self.0.truncate(MAX_HIGH_SCORES);
+        Some(index)
+    }
+
+    pub fn save(&self) {
+        save_ron(HIGH_SCORES_FILE, &self.0);
+    }
+}
+
+// 将秒数格式化为 mm:ss
+pub fn format_time(seconds: f32) -> String {
+    let seconds = seconds as u64;
+    format!("{:02}:{:02}", seconds / 60, seconds % 60)
+}
+
+// 今天的日期，使用 UTC
+pub fn today() -> String {
+    let days = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs() / 86400);
+    let (year, month, day) = civil_from_days(days as i64);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+// 将 1970-01-01 以来的天数转换为年月日
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn high_score(name: &str, score: usize, seconds: f32) -> HighScore {
+        HighScore {
+            name: name.into(),
+            score,
+            seconds,
+            date: "2024-01-01".into(),
+        }
+    }
+
+    #[test]
+    fn keeps_top_ten_sorted_by_score_then_time() {
+        let mut high_scores = HighScores(Vec::new());
+        for index in 0..MAX_HIGH_SCORES {
+            high_scores.insert(high_score("a", index * 10, 60.));
+        }
+        assert_eq!(high_scores.first().unwrap().score, 90);
+        assert_eq!(high_scores.last().unwrap().score, 0);
+
+        assert_eq!(high_scores.insert(high_score("b", 50, 30.)), Some(4));
+        assert_eq!(high_scores.len(), MAX_HIGH_SCORES);
+        assert_eq!(high_scores.last().unwrap().score, 10);
+
+        let slow = high_score("c", 10, 90.);
+        assert!(!high_scores.qualifies(&slow));
+        assert_eq!(high_scores.insert(slow), None);
+    }
+
+    #[test]
+    fn converts_days_to_dates() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(19_723), (2024, 1, 1));
+        assert_eq!(civil_from_days(19_782), (2024, 2, 29));
+        assert_eq!(format_time(125.7), "02:05");
+    }
+}
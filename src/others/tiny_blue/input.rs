@@ -0,0 +1,195 @@
+use std::collections::BTreeMap;
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::storage::{load_ron, save_ron};
+
+// 按键绑定，位于本地存档目录
+const BINDINGS_FILE: &str = "bindings.ron";
+// 左摇杆超过这个值时视为按下移动
+const STICK_THRESHOLD: f32 = 0.5;
+
+pub fn plugin(app: &mut App) {
+    app.init_resource::<InputBindings>();
+}
+
+// 游戏中的动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    Jump,
+    Pause,
+}
+
+impl Action {
+    pub const ALL: [Action; 4] = [
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Jump,
+        Action::Pause,
+    ];
+
+    // 动作名字的本地化键
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::MoveLeft => "action.move_left",
+            Action::MoveRight => "action.move_right",
+            Action::Jump => "action.jump",
+            Action::Pause => "action.pause",
+        }
+    }
+}
+
+// 一个动作的键盘按键和手柄按钮
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActionBinding {
+    pub key: KeyCode,
+    pub gamepad_button: GamepadButtonType,
+}
+
+// 所有动作的按键绑定
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct InputBindings(BTreeMap<Action, ActionBinding>);
+
+impl InputBindings {
+    // 默认的按键绑定
+    pub fn defaults() -> Self {
+        let binding = |key, gamepad_button| ActionBinding {
+            key,
+            gamepad_button,
+        };
+        Self(BTreeMap::from([
+            (
+                Action::MoveLeft,
+                binding(KeyCode::KeyA, GamepadButtonType::DPadLeft),
+            ),
+            (
+                Action::MoveRight,
+                binding(KeyCode::KeyD, GamepadButtonType::DPadRight),
+            ),
+            (
+                Action::Jump,
+                binding(KeyCode::KeyJ, GamepadButtonType::South),
+            ),
+            (
+                Action::Pause,
+                binding(KeyCode::Space, GamepadButtonType::Start),
+            ),
+        ]))
+    }
+
+    pub fn get(&self, action: Action) -> ActionBinding {
+        self.0[&action]
+    }
+
+    // 绑定键盘按键，按键已经被其他动作使用时，两个动作交换按键
+    pub fn bind_key(&mut self, action: Action, key: KeyCode) {
+        let old_key = self.get(action).key;
+        for binding in self.0.values_mut() {
+            if binding.key == key {
+                binding.key = old_key;
+            }
+        }
+        self.0.get_mut(&action).unwrap().key = key;
+    }
+
+    // 绑定手柄按钮，按钮已经被其他动作使用时，两个动作交换按钮
+    pub fn bind_gamepad_button(&mut self, action: Action, gamepad_button: GamepadButtonType) {
+        let old_gamepad_button = self.get(action).gamepad_button;
+        for binding in self.0.values_mut() {
+            if binding.gamepad_button == gamepad_button {
+                binding.gamepad_button = old_gamepad_button;
+            }
+        }
+        self.0.get_mut(&action).unwrap().gamepad_button = gamepad_button;
+    }
+
+    // 保存到本地存档
+    pub fn save(&self) {
+        save_ron(BINDINGS_FILE, &self.0);
+    }
+
+    // 动作的按键说明，例如 "A / DPadLeft"
+    pub fn binding_text(&self, action: Action) -> String {
+        let binding = self.get(action);
+        format!(
+            "{} / {}",
+            key_name(binding.key),
+            gamepad_button_name(binding.gamepad_button)
+        )
+    }
+}
+
+impl FromWorld for InputBindings {
+    // 读取本地存档，存档中缺少的动作使用默认按键
+    fn from_world(_world: &mut World) -> Self {
+        let mut bindings = Self::defaults();
+        bindings
+            .0
+            .extend(load_ron::<BTreeMap<Action, ActionBinding>>(BINDINGS_FILE));
+        bindings
+    }
+}
+
+// 键盘按键的名字，去掉 Key 和 Digit 前缀
+pub fn key_name(key: KeyCode) -> String {
+    let name = format!("{key:?}");
+    name.strip_prefix("Key")
+        .or_else(|| name.strip_prefix("Digit"))
+        .unwrap_or(&name)
+        .to_string()
+}
+
+// 手柄按钮的名字
+pub fn gamepad_button_name(gamepad_button: GamepadButtonType) -> String {
+    format!("{gamepad_button:?}")
+}
+
+// 按照按键绑定读取动作，任意一个手柄都可以控制，移动也可以使用左摇杆
+#[derive(SystemParam)]
+pub struct ActionInput<'w> {
+    bindings: Res<'w, InputBindings>,
+    keyboard: Res<'w, ButtonInput<KeyCode>>,
+    gamepads: Res<'w, Gamepads>,
+    gamepad_buttons: Res<'w, ButtonInput<GamepadButton>>,
+    gamepad_axes: Res<'w, Axis<GamepadAxis>>,
+}
+
+impl ActionInput<'_> {
+    // 动作正在被按下
+    pub fn pressed(&self, action: Action) -> bool {
+        let binding = self.bindings.get(action);
+        self.keyboard.pressed(binding.key)
+            || self.gamepads.iter().any(|gamepad| {
+                self.gamepad_buttons
+                    .pressed(GamepadButton::new(gamepad, binding.gamepad_button))
+            })
+            || self.stick_pressed(action)
+    }
+
+    // 动作刚刚被按下
+    pub fn just_pressed(&self, action: Action) -> bool {
+        let binding = self.bindings.get(action);
+        self.keyboard.just_pressed(binding.key)
+            || self.gamepads.iter().any(|gamepad| {
+                self.gamepad_buttons
+                    .just_pressed(GamepadButton::new(gamepad, binding.gamepad_button))
+            })
+    }
+
+    // 左摇杆控制移动
+    fn stick_pressed(&self, action: Action) -> bool {
+        let direction = match action {
+            Action::MoveLeft => -1.,
+            Action::MoveRight => 1.,
+            _ => return false,
+        };
+        self.gamepads.iter().any(|gamepad| {
+            self.gamepad_axes
+                .get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickX))
+                .is_some_and(|x| x * direction > STICK_THRESHOLD)
+        })
+    }
+}
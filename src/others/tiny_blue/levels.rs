@@ -0,0 +1,95 @@
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::storage::{load_asset_ron, load_ron, save_ron};
+
+// 关卡清单，位于资产目录
+const LEVELS_FILE: &str = "levels.ron";
+// 没有关卡清单时使用的场景
+const DEFAULT_SCENE: &str = "tiny_blue.glb";
+// 每个关卡的最高分
+const BEST_SCORES_FILE: &str = "best_scores.ron";
+
+pub fn plugin(app: &mut App) {
+    app.init_resource::<Levels>()
+        .init_resource::<CurrentLevel>()
+        .init_resource::<BestScores>();
+}
+
+// 关卡
+#[derive(Debug, Clone, Deserialize)]
+pub struct Level {
+    pub name: String,
+    pub scene: String,
+}
+
+// 按顺序排列的所有关卡
+#[derive(Resource, Debug, Deref)]
+pub struct Levels(Vec<Level>);
+
+impl Levels {
+    pub fn get_level(&self, current_level: CurrentLevel) -> &Level {
+        &self.0[current_level.0.min(self.0.len() - 1)]
+    }
+
+    // 下一关，已经是最后一关时返回 None
+    pub fn next(&self, current_level: CurrentLevel) -> Option<CurrentLevel> {
+        let next = current_level.0 + 1;
+        (next < self.0.len()).then_some(CurrentLevel(next))
+    }
+
+    fn single() -> Self {
+        Self(vec![Level {
+            name: "levels.first".into(),
+            scene: DEFAULT_SCENE.into(),
+        }])
+    }
+}
+
+impl Default for Levels {
+    fn default() -> Self {
+        match load_asset_ron::<Vec<Level>>(LEVELS_FILE) {
+            Ok(levels) if !levels.is_empty() => Self(levels),
+            Ok(_) => {
+                warn!("{LEVELS_FILE} has no levels, use {DEFAULT_SCENE}");
+                Self::single()
+            }
+            Err(error) => {
+                warn!("{LEVELS_FILE} load failed: {error}, use {DEFAULT_SCENE}");
+                Self::single()
+            }
+        }
+    }
+}
+
+// 当前关卡的索引
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CurrentLevel(pub usize);
+
+// 每个关卡的最高分，按关卡场景记录
+#[derive(Resource, Debug)]
+pub struct BestScores(BTreeMap<String, usize>);
+
+impl FromWorld for BestScores {
+    fn from_world(_world: &mut World) -> Self {
+        Self(load_ron(BEST_SCORES_FILE))
+    }
+}
+
+impl BestScores {
+    pub fn get(&self, level: &Level) -> Option<usize> {
+        self.0.get(&level.scene).copied()
+    }
+
+    // 记录分数，超过最高分时保存，返回是否是新纪录
+    pub fn record(&mut self, level: &Level, score: usize) -> bool {
+        if self.get(level).is_some_and(|best| best >= score) {
+            return false;
+        }
+        self.0.insert(level.scene.clone(), score);
+        save_ron(BEST_SCORES_FILE, &self.0);
+        true
+    }
+}
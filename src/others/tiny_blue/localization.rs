@@ -0,0 +1,207 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Mutex,
+};
+
+use bevy::{prelude::*, ui::UiSystem};
+
+use crate::{
+    settings::{GameSettings, Language},
+    storage::load_asset_ron,
+};
+
+// 字符串表所在的资产子目录，每种语言一个 RON 文件
+const LOCALES_DIR: &str = "locales";
+
+pub fn plugin(app: &mut App) {
+    app.init_resource::<Localization>()
+        .add_systems(
+            Update,
+            switch_language.run_if(resource_changed::<GameSettings>),
+        )
+        // 在布局之前重新生成文本，同一帧中生成的文本不会先显示为空
+        .add_systems(PostUpdate, update_localized_texts.before(UiSystem::Layout));
+}
+
+// 当前语言的字符串表
+#[derive(Resource, Debug)]
+pub struct Localization {
+    language: Language,
+    strings: HashMap<String, String>,
+    // 已经警告过的缺失的键
+    missing_keys: Mutex<HashSet<String>>,
+}
+
+impl Localization {
+    // 读取一种语言的字符串表，读取失败时所有文本为空
+    pub fn load(language: Language) -> Self {
+        let file_name = format!("{LOCALES_DIR}/{}.ron", language.code());
+        let strings = load_asset_ron(&file_name).unwrap_or_else(|error| {
+            warn!("{file_name} load failed: {error}");
+            HashMap::new()
+        });
+        Self::new(language, strings)
+    }
+
+    fn new(language: Language, strings: HashMap<String, String>) -> Self {
+        Self {
+            language,
+            strings,
+            missing_keys: Mutex::default(),
+        }
+    }
+
+    // 键对应的文本，缺失的键显示为空，每个键只警告一次
+    pub fn get(&self, key: &str) -> &str {
+        if let Some(text) = self.strings.get(key) {
+            return text;
+        }
+        if self.missing_keys.lock().unwrap().insert(key.into()) {
+            warn!("missing {} text for key `{key}`", self.language.code());
+        }
+        ""
+    }
+
+    // 文本的内容，字面量直接使用
+    pub fn text<'a>(&'a self, text: &'a TextSource) -> &'a str {
+        match text {
+            TextSource::Key(key) => self.get(key),
+            TextSource::Literal(literal) => literal,
+        }
+    }
+
+    // 生成文本，并将 {name} 替换为参数
+    pub fn format(&self, text: &TextSource, args: &[(&'static str, TextSource)]) -> String {
+        args.iter()
+            .fold(self.text(text).to_string(), |formatted, (name, value)| {
+                formatted.replace(&format!("{{{name}}}"), self.text(value))
+            })
+    }
+}
+
+impl FromWorld for Localization {
+    fn from_world(world: &mut World) -> Self {
+        let language = world
+            .get_resource::<GameSettings>()
+            .map_or_else(Language::default, |settings| settings.language);
+        Self::load(language)
+    }
+}
+
+// 文本的来源，键在字符串表中查找，字面量是玩家名字、分数、分辨率等直接显示的数据
+#[derive(Debug, Clone, PartialEq)]
+pub enum TextSource {
+    Key(String),
+    Literal(String),
+}
+
+// 需要本地化的文本，写入 Text 的第一段，切换语言或者修改这个组件后重新生成
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct LocalizedText {
+    text: TextSource,
+    args: Vec<(&'static str, TextSource)>,
+}
+
+impl LocalizedText {
+    // 字符串表中键对应的文本
+    pub fn key(key: impl Into<String>) -> Self {
+        Self::from_source(TextSource::Key(key.into()))
+    }
+
+    // 直接显示的文本
+    pub fn literal(text: impl Into<String>) -> Self {
+        Self::from_source(TextSource::Literal(text.into()))
+    }
+
+    fn from_source(text: TextSource) -> Self {
+        Self {
+            text,
+            args: Vec::new(),
+        }
+    }
+
+    // 添加直接显示的参数，替换文本中的 {name}
+    pub fn with_arg(mut self, name: &'static str, value: impl ToString) -> Self {
+        self.args
+            .push((name, TextSource::Literal(value.to_string())));
+        self
+    }
+
+    // 添加键对应的文本作为参数，替换文本中的 {name}
+    pub fn with_key_arg(mut self, name: &'static str, key: impl Into<String>) -> Self {
+        self.args.push((name, TextSource::Key(key.into())));
+        self
+    }
+}
+
+// 设置中的语言改变后读取新的字符串表
+fn switch_language(settings: Res<GameSettings>, mut localization: ResMut<Localization>) {
+    if settings.language != localization.language {
+        *localization = Localization::load(settings.language);
+    }
+}
+
+// 重新生成新添加、修改过或者切换语言后的文本
+fn update_localized_texts(
+    localization: Res<Localization>,
+    mut texts: Query<(Ref<LocalizedText>, &mut Text)>,
+) {
+    for (localized_text, mut text) in &mut texts {
+        if !localization.is_changed() && !localized_text.is_changed() {
+            continue;
+        }
+        text.sections[0].value = localization.format(&localized_text.text, &localized_text.args);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn localization() -> Localization {
+        Localization::new(
+            Language::English,
+            [
+                ("hud.score", "Score: "),
+                ("level.complete", "{level} complete!"),
+                ("level.first", "Level 1"),
+            ]
+            .into_iter()
+            .map(|(key, text)| (key.to_string(), text.to_string()))
+            .collect(),
+        )
+    }
+
+    #[test]
+    fn formats_key_and_literal_args() {
+        let localization = localization();
+        assert_eq!(localization.get("hud.score"), "Score: ");
+        assert_eq!(localization.get("level.missing"), "");
+        let format = |text: LocalizedText| localization.format(&text.text, &text.args);
+        assert_eq!(
+            format(LocalizedText::key("level.complete").with_key_arg("level", "level.first")),
+            "Level 1 complete!"
+        );
+        // 字面量参数即使与键同名也不会被翻译
+        assert_eq!(
+            format(LocalizedText::key("level.complete").with_arg("level", "level.first")),
+            "level.first complete!"
+        );
+        assert_eq!(format(LocalizedText::literal("1920x1080")), "1920x1080");
+    }
+
+    #[test]
+    fn languages_have_the_same_keys() {
+        let keys = |language| {
+            Localization::load(language)
+                .strings
+                .into_keys()
+                .collect::<HashSet<_>>()
+        };
+        let chinese = keys(Language::Chinese);
+        assert!(!chinese.is_empty());
+        for language in Language::ALL {
+            assert_eq!(keys(language), chinese, "{language:?}");
+        }
+    }
+}
@@ -1,8 +1,21 @@
+#![allow(clippy::type_complexity)]
+
+mod audio;
 mod game;
+mod high_scores;
+mod input;
+mod levels;
+mod localization;
+mod run_stats;
+mod save;
+mod settings;
 mod start_menu;
+mod storage;
 
 use avian2d::prelude::*;
 use bevy::{color::palettes::tailwind, prelude::*};
+use bevy_games::blender_editor::BlenderScenesPlugin;
+use localization::LocalizedText;
 // use bevy_inspector_egui::quick::WorldInspectorPlugin;
 
 // 处于不同状态下的按钮颜色
@@ -10,30 +23,45 @@ const BUTTON_HOVERED: Color = Color::Srgba(tailwind::FUCHSIA_500);
 const BUTTON_PRESSED: Color = Color::Srgba(tailwind::RED_600);
 const BUTTON_NONE: Color = Color::Srgba(tailwind::ORANGE_500);
 
+// 资产目录
+const ASSETS_PATH: &str = "assets/tiny_blue";
+
 fn main() -> AppExit {
+    let settings = settings::GameSettings::load();
     App::new()
         .add_plugins((
             DefaultPlugins
                 .set(AssetPlugin {
-                    file_path: "assets/tiny_blue".into(),
+                    file_path: ASSETS_PATH.into(),
                     ..default()
                 })
                 .set(WindowPlugin {
                     primary_window: Some(Window {
                         title: "Tiny Blue".into(),
-                        resizable: false,
-                        ..default()
+                        ..settings.window()
                     }),
                     ..default()
                 }),
             PhysicsPlugins::default(),
+            BlenderScenesPlugin,
         ))
+        // 本地化等插件初始化时需要读取设置
+        .insert_resource(settings)
         // 开发时使用的插件
         // .add_plugins((
         //     WorldInspectorPlugin::default(),
         //     PhysicsDebugPlugin::default(),
         // ))
-        .add_plugins((start_menu::plugin, game::plugin))
+        .add_plugins((
+            audio::plugin,
+            levels::plugin,
+            high_scores::plugin,
+            input::plugin,
+            settings::plugin,
+            localization::plugin,
+            start_menu::plugin,
+            game::plugin,
+        ))
         .init_state::<AppState>()
         .enable_state_scoped_entities::<AppState>()
         .init_resource::<AppDefaultFont>()
@@ -48,6 +76,7 @@ enum AppState {
     Game,
 }
 
+// 默认字体，NotoSansSC 同时包含中文和拉丁字符，所有语言共用
 #[derive(Resource, Deref)]
 struct AppDefaultFont(Handle<Font>);
 
@@ -74,7 +103,7 @@ fn switch_button_background_color(
 fn spawn_button<T: Bundle>(
     parent: &mut ChildBuilder,
     marker: T,
-    text: &str,
+    text_key: &str,
     font: Handle<Font>,
     margin: UiRect,
 ) {
@@ -98,13 +127,16 @@ fn spawn_button<T: Bundle>(
             marker,
         ))
         .with_children(|parent| {
-            parent.spawn(TextBundle::from_section(
-                text,
-                TextStyle {
-                    font,
-                    font_size: 90.,
-                    color: Color::WHITE,
-                },
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font,
+                        font_size: 90.,
+                        color: Color::WHITE,
+                    },
+                ),
+                LocalizedText::key(text_key),
             ));
         });
 }
@@ -0,0 +1,71 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+// 每关的标准用时，通关用时少于标准用时的部分获得奖励
+const PAR_SECONDS_PER_LEVEL: f32 = 120.;
+// 每少用一秒获得的奖励分数
+const TIME_BONUS_PER_SECOND: f32 = 5.;
+
+// 整局游戏的统计，随存档保存
+#[derive(Resource, Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RunStats {
+    // 游戏用时，只在 GamePhase::Playing 时计时
+    pub seconds: f32,
+    // 之前关卡的分数之和
+    pub banked_score: usize,
+    // 已经完成的关卡数
+    pub cleared_levels: usize,
+    pub deaths: usize,
+    pub jumps: usize,
+    // 在地面上行走的距离
+    pub distance: f32,
+}
+
+impl RunStats {
+    // 整局游戏的分数，score 是当前关卡的分数
+    pub fn total_score(&self, score: usize) -> usize {
+        self.banked_score + score
+    }
+
+    // 时间奖励：已完成关卡的标准用时减去实际用时，每秒奖励 TIME_BONUS_PER_SECOND 分
+    pub fn time_bonus(&self) -> usize {
+        let saved_seconds = PAR_SECONDS_PER_LEVEL * self.cleared_levels as f32 - self.seconds;
+        (saved_seconds.max(0.) * TIME_BONUS_PER_SECOND) as usize
+    }
+
+    // 加上时间奖励后的最终分数
+    pub fn final_score(&self, score: usize) -> usize {
+        self.total_score(score) + self.time_bonus()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn time_bonus_rewards_fast_clears_only() {
+        let run_stats = RunStats {
+            seconds: 200.,
+            banked_score: 50,
+            cleared_levels: 2,
+            ..default()
+        };
+        assert_eq!(run_stats.time_bonus(), 200);
+        assert_eq!(run_stats.final_score(30), 280);
+
+        let failed = RunStats {
+            seconds: 30.,
+            ..default()
+        };
+        assert_eq!(failed.time_bonus(), 0);
+
+        let slow = RunStats {
+            seconds: 500.,
+            cleared_levels: 2,
+            ..default()
+        };
+        assert_eq!(slow.time_bonus(), 0);
+    }
+}
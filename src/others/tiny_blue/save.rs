@@ -0,0 +1,140 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    run_stats::RunStats,
+    storage::{remove_data, save_ron, try_load_ron},
+};
+
+// 游戏存档，位于本地存档目录
+const SAVE_FILE: &str = "save.ron";
+
+// 游戏进度存档，暂停、打开菜单和退出游戏时写入
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SaveState {
+    // 当前关卡的索引
+    pub level: usize,
+    pub score: usize,
+    // 已经吃掉的食物，使用场景中的节点名字标识
+    pub collected_foods: Vec<String>,
+    // 玩家位置，为 None 时使用场景中的初始位置
+    pub player_position: Option<Vec2>,
+    pub lives: usize,
+    // 整局游戏的统计，旧存档中没有时使用默认值
+    #[serde(default)]
+    pub run_stats: RunStats,
+    // 当前关卡持有的钥匙数和已经打开的门，旧存档中没有时使用默认值
+    #[serde(default)]
+    pub keys: usize,
+    #[serde(default)]
+    pub opened_doors: Vec<String>,
+    // 当前关卡的检查点，旧存档中没有时使用玩家位置
+    #[serde(default)]
+    pub checkpoint: Option<Vec2>,
+}
+
+impl SaveState {
+    // 读取存档，没有存档时返回 None
+    pub fn load() -> Option<Self> {
+        try_load_ron(SAVE_FILE)
+    }
+
+    pub fn save(&self) {
+        save_ron(SAVE_FILE, self);
+    }
+
+    // 删除存档，游戏结束后不能继续
+    pub fn remove() {
+        remove_data(SAVE_FILE);
+    }
+}
+
+// 点击继续游戏后等待恢复的存档，进入 GamePhase::Playing 时恢复并删除
+#[derive(Resource, Debug, Deref)]
+pub struct PendingSave(pub SaveState);
+
+#[cfg(test)]
+mod tests {
+    use bevy::scene::ron;
+
+    use super::*;
+
+    fn save_state() -> SaveState {
+        SaveState {
+            level: 1,
+            score: 30,
+            collected_foods: vec!["food.001".into(), "food.004".into()],
+            player_position: Some(Vec2::new(-12.5, 0.75)),
+            lives: 2,
+            run_stats: RunStats {
+                seconds: 95.5,
+                banked_score: 120,
+                cleared_levels: 1,
+                deaths: 1,
+                jumps: 14,
+                distance: 42.25,
+            },
+            keys: 1,
+            opened_doors: vec!["door.002".into()],
+            checkpoint: Some(Vec2::new(-14., 0.5)),
+        }
+    }
+
+    #[test]
+    fn save_state_round_trips() {
+        let save_state = save_state();
+        let ron_string =
+            ron::ser::to_string_pretty(&save_state, ron::ser::PrettyConfig::default()).unwrap();
+        assert_eq!(ron::from_str::<SaveState>(&ron_string).unwrap(), save_state);
+
+        let save_state = SaveState {
+            player_position: None,
+            collected_foods: Vec::new(),
+            ..save_state
+        };
+        let ron_string = ron::to_string(&save_state).unwrap();
+        assert_eq!(ron::from_str::<SaveState>(&ron_string).unwrap(), save_state);
+    }
+
+    #[test]
+    fn reads_save_file_format() {
+        let ron_string = r#"(
+            level: 1,
+            score: 30,
+            collected_foods: ["food.001", "food.004"],
+            player_position: Some((-12.5, 0.75)),
+            lives: 2,
+            run_stats: (
+                seconds: 95.5,
+                banked_score: 120,
+                cleared_levels: 1,
+                deaths: 1,
+                jumps: 14,
+                distance: 42.25,
+            ),
+            keys: 1,
+            opened_doors: ["door.002"],
+            checkpoint: Some((-14.0, 0.5)),
+        )"#;
+        assert_eq!(
+            ron::from_str::<SaveState>(ron_string).unwrap(),
+            save_state()
+        );
+    }
+
+    #[test]
+    fn reads_save_file_without_run_stats() {
+        let ron_string = r#"(
+            level: 0,
+            score: 10,
+            collected_foods: ["food.001"],
+            player_position: None,
+            lives: 3,
+        )"#;
+        let save_state = ron::from_str::<SaveState>(ron_string).unwrap();
+        assert_eq!(save_state.run_stats, RunStats::default());
+        assert_eq!(save_state.keys, 0);
+        assert!(save_state.opened_doors.is_empty());
+        assert_eq!(save_state.checkpoint, None);
+    }
+}
@@ -0,0 +1,232 @@
+use bevy::{
+    prelude::*,
+    window::{PresentMode, PrimaryWindow, WindowMode, WindowResolution},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    localization::LocalizedText,
+    storage::{load_ron, save_ron},
+};
+
+// 游戏设置，位于本地存档目录
+const SETTINGS_FILE: &str = "settings.ron";
+// 可以选择的窗口分辨率
+const RESOLUTIONS: [UVec2; 4] = [
+    UVec2::new(1280, 720),
+    UVec2::new(1600, 900),
+    UVec2::new(1920, 1080),
+    UVec2::new(2560, 1440),
+];
+// 可以选择的窗口模式
+const WINDOW_MODES: [WindowMode; 3] = [
+    WindowMode::Windowed,
+    WindowMode::BorderlessFullscreen,
+    WindowMode::Fullscreen,
+];
+// 每次调整音量的步长
+const VOLUME_STEP: f32 = 0.1;
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (apply_window_settings, apply_volume_settings).run_if(resource_changed::<GameSettings>),
+    );
+}
+
+// 界面语言
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    Chinese,
+    English,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::Chinese, Language::English];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Language::Chinese => "中文",
+            Language::English => "English",
+        }
+    }
+
+    // 字符串表的文件名
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::Chinese => "zh",
+            Language::English => "en",
+        }
+    }
+}
+
+// 可以修改的设置项
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingKind {
+    WindowMode,
+    Resolution,
+    Vsync,
+    MasterVolume,
+    MusicVolume,
+    SfxVolume,
+    Language,
+}
+
+impl SettingKind {
+    pub const ALL: [SettingKind; 7] = [
+        SettingKind::WindowMode,
+        SettingKind::Resolution,
+        SettingKind::Vsync,
+        SettingKind::MasterVolume,
+        SettingKind::MusicVolume,
+        SettingKind::SfxVolume,
+        SettingKind::Language,
+    ];
+
+    // 设置项名字的本地化键
+    pub fn label(self) -> &'static str {
+        match self {
+            SettingKind::WindowMode => "settings.window_mode",
+            SettingKind::Resolution => "settings.resolution",
+            SettingKind::Vsync => "settings.vsync",
+            SettingKind::MasterVolume => "settings.master_volume",
+            SettingKind::MusicVolume => "settings.music_volume",
+            SettingKind::SfxVolume => "settings.sfx_volume",
+            SettingKind::Language => "settings.language",
+        }
+    }
+}
+
+// 游戏设置，启动时读取，修改后立即保存并应用
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameSettings {
+    pub window_mode: WindowMode,
+    pub resolution: UVec2,
+    pub vsync: bool,
+    // 音量的范围都是 0 到 1
+    pub master_volume: f32,
+    pub music_volume: f32,
+    pub sfx_volume: f32,
+    pub language: Language,
+}
+
+impl Default for GameSettings {
+    fn default() -> Self {
+        Self {
+            window_mode: WindowMode::Windowed,
+            resolution: RESOLUTIONS[0],
+            vsync: true,
+            master_volume: 1.,
+            music_volume: 0.8,
+            sfx_volume: 0.8,
+            language: Language::default(),
+        }
+    }
+}
+
+impl GameSettings {
+    // 读取本地存档
+    pub fn load() -> Self {
+        load_ron(SETTINGS_FILE)
+    }
+
+    pub fn save(&self) {
+        save_ron(SETTINGS_FILE, self);
+    }
+
+    // 根据设置生成主窗口
+    pub fn window(&self) -> Window {
+        Window {
+            mode: self.window_mode,
+            resolution: WindowResolution::new(self.resolution.x as f32, self.resolution.y as f32),
+            present_mode: self.present_mode(),
+            ..default()
+        }
+    }
+
+    pub fn present_mode(&self) -> PresentMode {
+        if self.vsync {
+            PresentMode::AutoVsync
+        } else {
+            PresentMode::AutoNoVsync
+        }
+    }
+
+    // 切换到设置项的下一个值，音量到最大后回到 0
+    pub fn cycle(&mut self, kind: SettingKind) {
+        match kind {
+            SettingKind::WindowMode => self.window_mode = next(&WINDOW_MODES, self.window_mode),
+            SettingKind::Resolution => self.resolution = next(&RESOLUTIONS, self.resolution),
+            SettingKind::Vsync => self.vsync = !self.vsync,
+            SettingKind::MasterVolume => self.master_volume = next_volume(self.master_volume),
+            SettingKind::MusicVolume => self.music_volume = next_volume(self.music_volume),
+            SettingKind::SfxVolume => self.sfx_volume = next_volume(self.sfx_volume),
+            SettingKind::Language => self.language = next(&Language::ALL, self.language),
+        }
+    }
+
+    // 设置项当前值的文本，需要翻译的值使用本地化键，数值和分辨率直接显示
+    pub fn value_text(&self, kind: SettingKind) -> LocalizedText {
+        let volume_text =
+            |volume: f32| LocalizedText::literal(format!("{}%", (volume * 100.).round()));
+        match kind {
+            SettingKind::WindowMode => LocalizedText::key(match self.window_mode {
+                WindowMode::Windowed => "settings.windowed",
+                WindowMode::BorderlessFullscreen => "settings.borderless",
+                WindowMode::SizedFullscreen | WindowMode::Fullscreen => "settings.fullscreen",
+            }),
+            SettingKind::Resolution => {
+                LocalizedText::literal(format!("{}x{}", self.resolution.x, self.resolution.y))
+            }
+            SettingKind::Vsync => LocalizedText::key(if self.vsync {
+                "settings.on"
+            } else {
+                "settings.off"
+            }),
+            SettingKind::MasterVolume => volume_text(self.master_volume),
+            SettingKind::MusicVolume => volume_text(self.music_volume),
+            SettingKind::SfxVolume => volume_text(self.sfx_volume),
+            // 语言名字使用它自己的语言显示，不翻译
+            SettingKind::Language => LocalizedText::literal(self.language.label()),
+        }
+    }
+}
+
+// 列表中的下一个值，不在列表中时使用第一个值
+fn next<T: Copy + PartialEq>(values: &[T], current: T) -> T {
+    let index = values
+        .iter()
+        .position(|&value| value == current)
+        .map_or(0, |index| (index + 1) % values.len());
+    values[index]
+}
+
+fn next_volume(volume: f32) -> f32 {
+    if volume >= 1. - VOLUME_STEP / 2. {
+        0.
+    } else {
+        ((volume + VOLUME_STEP) / VOLUME_STEP).round() * VOLUME_STEP
+    }
+}
+
+// 修改主窗口
+fn apply_window_settings(
+    mut window: Query<&mut Window, With<PrimaryWindow>>,
+    settings: Res<GameSettings>,
+) {
+    let Ok(mut window) = window.get_single_mut() else {
+        return;
+    };
+    window.mode = settings.window_mode;
+    window
+        .resolution
+        .set(settings.resolution.x as f32, settings.resolution.y as f32);
+    window.present_mode = settings.present_mode();
+}
+
+// 修改全局音量，只影响之后播放的声音
+fn apply_volume_settings(mut global_volume: ResMut<GlobalVolume>, settings: Res<GameSettings>) {
+    *global_volume = GlobalVolume::new(settings.master_volume);
+}
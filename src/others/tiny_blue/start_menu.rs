@@ -1,31 +1,157 @@
 use bevy::{color::palettes::tailwind, prelude::*};
 
-use crate::{spawn_button, AppDefaultFont, AppState};
+use crate::{
+    audio::{play_music, MusicTrack},
+    high_scores::{format_time, HighScores},
+    input::{gamepad_button_name, key_name, Action, InputBindings},
+    levels::{BestScores, CurrentLevel, Levels},
+    localization::LocalizedText,
+    save::{PendingSave, SaveState},
+    settings::{GameSettings, SettingKind},
+    spawn_button, AppDefaultFont, AppState, BUTTON_NONE,
+};
 
 pub fn plugin(app: &mut App) {
-    app.add_systems(OnEnter(AppState::StartMenu), (spawn_ui_camera, spawn_start_menu_page))
+    app.add_sub_state::<StartMenuPage>()
+        .enable_state_scoped_entities::<StartMenuPage>()
+        .add_systems(
+            OnEnter(AppState::StartMenu),
+            (spawn_ui_camera, play_music(MusicTrack::Menu)),
+        )
+        .add_systems(OnEnter(StartMenuPage::Main), spawn_start_menu_page)
+        .add_systems(OnEnter(StartMenuPage::LevelSelect), spawn_level_select_page)
+        .add_systems(OnEnter(StartMenuPage::Controls), spawn_controls_page)
+        .add_systems(OnEnter(StartMenuPage::Settings), spawn_settings_page)
+        .add_systems(OnEnter(StartMenuPage::HighScores), spawn_high_scores_page)
+        .add_systems(OnExit(StartMenuPage::Controls), remove_rebinding)
+        .add_systems(
+            Update,
+            (
+                pressed_continue_button,
+                pressed_start_game_button,
+                pressed_level_select_button,
+                pressed_high_scores_button,
+                pressed_controls_button,
+                pressed_settings_button,
+                pressed_exit_game_button,
+            )
+                .run_if(in_state(StartMenuPage::Main)),
+        )
+        .add_systems(
+            Update,
+            (pressed_level_button, pressed_back_button)
+                .run_if(in_state(StartMenuPage::LevelSelect)),
+        )
+        .add_systems(
+            Update,
+            pressed_back_button.run_if(in_state(StartMenuPage::HighScores)),
+        )
+        .add_systems(
+            Update,
+            (
+                pressed_binding_button,
+                capture_binding.run_if(resource_exists::<Rebinding>),
+                pressed_reset_bindings_button,
+                update_binding_text,
+                pressed_back_button,
+            )
+                .chain()
+                .run_if(in_state(StartMenuPage::Controls)),
+        )
         .add_systems(
             Update,
-            (pressed_start_game_button, pressed_exit_game_button)
-                .run_if(in_state(AppState::StartMenu)),
+            (
+                pressed_setting_button,
+                update_setting_text,
+                pressed_back_button,
+            )
+                .chain()
+                .run_if(in_state(StartMenuPage::Settings)),
         );
 }
 
+// 开始菜单的不同页面
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash, SubStates)]
+#[source(AppState = AppState::StartMenu)]
+enum StartMenuPage {
+    #[default]
+    Main, // 开始菜单
+    LevelSelect, // 选择关卡
+    Controls,    // 按键设置
+    Settings,    // 设置
+    HighScores,  // 排行榜
+}
+
+// 继续游戏按钮
+#[derive(Component)]
+struct ContinueButton;
+
 // 开始游戏按钮
 #[derive(Component)]
 struct StartGameButton;
 
+// 选择关卡按钮
+#[derive(Component)]
+struct LevelSelectButton;
+
+// 排行榜按钮
+#[derive(Component)]
+struct HighScoresButton;
+
+// 按键设置按钮
+#[derive(Component)]
+struct ControlsButton;
+
+// 设置按钮
+#[derive(Component)]
+struct SettingsButton;
+
 // 退出游戏按钮
 #[derive(Component)]
 struct ExitGameButton;
 
+// 关卡按钮
+#[derive(Component)]
+struct LevelButton(usize);
+
+// 返回开始菜单主页面按钮
+#[derive(Component)]
+struct BackButton;
+
+// 按键绑定的设备
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BindingDevice {
+    Keyboard,
+    Gamepad,
+}
+
+// 修改一个动作的按键绑定的按钮
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+struct BindingButton {
+    action: Action,
+    device: BindingDevice,
+}
+
+// 切换一个设置项的按钮
+#[derive(Component)]
+struct SettingButton(SettingKind);
+
+// 恢复默认按键按钮
+#[derive(Component)]
+struct ResetBindingsButton;
+
+// 正在等待新按键的绑定按钮
+#[derive(Resource, Deref)]
+struct Rebinding(BindingButton);
+
 // 生成 UI 相机
 fn spawn_ui_camera(mut commands: Commands) {
     commands.spawn((Camera2dBundle::default(), StateScoped(AppState::StartMenu)));
 }
 
-// 生成开始菜单
+// 生成开始菜单，有存档时显示继续游戏按钮
 fn spawn_start_menu_page(mut commands: Commands, default_font: Res<AppDefaultFont>) {
+    let has_save = SaveState::load().is_some();
     commands
         .spawn((
             NodeBundle {
@@ -39,32 +165,305 @@ fn spawn_start_menu_page(mut commands: Commands, default_font: Res<AppDefaultFon
                 background_color: BackgroundColor(tailwind::GRAY_600.into()),
                 ..default()
             },
-            StateScoped(AppState::StartMenu),
+            StateScoped(StartMenuPage::Main),
         ))
         .with_children(|parent| {
             parent
                 .spawn(NodeBundle {
                     style: Style {
-                        flex_direction: FlexDirection::Column,
-                        height: Val::Percent(100.),
-                        justify_content: JustifyContent::Center,
-                        align_items: AlignItems::Center,
+                        display: Display::Grid,
+                        grid_template_columns: RepeatedGridTrack::auto(2),
+                        column_gap: Val::Px(40.),
+                        row_gap: Val::Px(30.),
                         ..default()
                     },
                     ..default()
                 })
                 .with_children(|parent| {
+                    if has_save {
+                        spawn_button(
+                            parent,
+                            ContinueButton,
+                            "menu.continue",
+                            default_font.clone(),
+                            UiRect::ZERO,
+                        );
+                    }
                     spawn_button(
                         parent,
                         StartGameButton,
-                        "开始游戏",
+                        "menu.start_game",
+                        default_font.clone(),
+                        UiRect::ZERO,
+                    );
+                    spawn_button(
+                        parent,
+                        LevelSelectButton,
+                        "menu.level_select",
+                        default_font.clone(),
+                        UiRect::ZERO,
+                    );
+                    spawn_button(
+                        parent,
+                        HighScoresButton,
+                        "menu.high_scores",
                         default_font.clone(),
-                        UiRect::bottom(Val::Percent(20.)),
+                        UiRect::ZERO,
+                    );
+                    spawn_button(
+                        parent,
+                        ControlsButton,
+                        "menu.controls",
+                        default_font.clone(),
+                        UiRect::ZERO,
+                    );
+                    spawn_button(
+                        parent,
+                        SettingsButton,
+                        "menu.settings",
+                        default_font.clone(),
+                        UiRect::ZERO,
                     );
                     spawn_button(
                         parent,
                         ExitGameButton,
-                        "退出游戏",
+                        "menu.exit",
+                        default_font.clone(),
+                        UiRect::ZERO,
+                    );
+                });
+        });
+}
+
+// 生成选择关卡页面，每个关卡显示最高分
+fn spawn_level_select_page(
+    mut commands: Commands,
+    default_font: Res<AppDefaultFont>,
+    levels: Res<Levels>,
+    best_scores: Res<BestScores>,
+) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    width: Val::Percent(100.),
+                    height: Val::Percent(100.),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: BackgroundColor(tailwind::GRAY_600.into()),
+                ..default()
+            },
+            StateScoped(StartMenuPage::LevelSelect),
+        ))
+        .with_children(|parent| {
+            for (index, level) in levels.iter().enumerate() {
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            align_items: AlignItems::Center,
+                            margin: UiRect::bottom(Val::Px(20.)),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        spawn_button(
+                            parent,
+                            LevelButton(index),
+                            &level.name,
+                            default_font.clone(),
+                            UiRect::right(Val::Px(40.)),
+                        );
+                        let best_score = best_scores
+                            .get(level)
+                            .map_or_else(|| "-".into(), |best_score| best_score.to_string());
+                        parent.spawn((
+                            TextBundle::from_section(
+                                "",
+                                TextStyle {
+                                    font: default_font.clone(),
+                                    font_size: 60.,
+                                    color: Color::WHITE,
+                                },
+                            ),
+                            LocalizedText::key("level_select.best_score")
+                                .with_arg("score", best_score),
+                        ));
+                    });
+            }
+            spawn_button(
+                parent,
+                BackButton,
+                "common.back",
+                default_font.clone(),
+                UiRect::top(Val::Px(20.)),
+            );
+        });
+}
+
+// 生成排行榜页面，每条记录显示名次、名字、分数、用时和日期
+fn spawn_high_scores_page(
+    mut commands: Commands,
+    default_font: Res<AppDefaultFont>,
+    high_scores: Res<HighScores>,
+) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    width: Val::Percent(100.),
+                    height: Val::Percent(100.),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: BackgroundColor(tailwind::GRAY_600.into()),
+                ..default()
+            },
+            StateScoped(StartMenuPage::HighScores),
+        ))
+        .with_children(|parent| {
+            let text_style = TextStyle {
+                font: default_font.clone(),
+                font_size: 40.,
+                color: Color::WHITE,
+            };
+            if high_scores.is_empty() {
+                parent.spawn((
+                    TextBundle::from_section("", text_style),
+                    LocalizedText::key("high_scores.empty"),
+                ));
+            } else {
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            display: Display::Grid,
+                            grid_template_columns: RepeatedGridTrack::auto(5),
+                            column_gap: Val::Px(60.),
+                            row_gap: Val::Px(10.),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        for header in [
+                            "high_scores.rank",
+                            "high_scores.name",
+                            "high_scores.score",
+                            "high_scores.time",
+                            "high_scores.date",
+                        ] {
+                            parent.spawn((
+                                TextBundle::from_section(
+                                    "",
+                                    TextStyle {
+                                        color: tailwind::PINK_400.into(),
+                                        ..text_style.clone()
+                                    },
+                                ),
+                                LocalizedText::key(header),
+                            ));
+                        }
+                        for (index, high_score) in high_scores.iter().enumerate() {
+                            for cell in [
+                                (index + 1).to_string(),
+                                high_score.name.clone(),
+                                high_score.score.to_string(),
+                                format_time(high_score.seconds),
+                                high_score.date.clone(),
+                            ] {
+                                parent.spawn(TextBundle::from_section(cell, text_style.clone()));
+                            }
+                        }
+                    });
+            }
+            spawn_button(
+                parent,
+                BackButton,
+                "common.back",
+                default_font.clone(),
+                UiRect::top(Val::Px(40.)),
+            );
+        });
+}
+
+// 生成按键设置页面，每个动作一行，分别是键盘按键和手柄按钮
+fn spawn_controls_page(mut commands: Commands, default_font: Res<AppDefaultFont>) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    width: Val::Percent(100.),
+                    height: Val::Percent(100.),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: BackgroundColor(tailwind::GRAY_600.into()),
+                ..default()
+            },
+            StateScoped(StartMenuPage::Controls),
+        ))
+        .with_children(|parent| {
+            let text_style = TextStyle {
+                font: default_font.clone(),
+                font_size: 50.,
+                color: Color::WHITE,
+            };
+            for action in Action::ALL {
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            align_items: AlignItems::Center,
+                            column_gap: Val::Px(20.),
+                            margin: UiRect::bottom(Val::Px(20.)),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        parent.spawn((
+                            TextBundle::from_section("", text_style.clone()).with_style(Style {
+                                width: Val::Px(240.),
+                                ..default()
+                            }),
+                            LocalizedText::key(action.label()),
+                        ));
+                        for device in [BindingDevice::Keyboard, BindingDevice::Gamepad] {
+                            spawn_binding_button(
+                                parent,
+                                BindingButton { action, device },
+                                text_style.clone(),
+                            );
+                        }
+                    });
+            }
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        column_gap: Val::Px(40.),
+                        margin: UiRect::top(Val::Px(20.)),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    spawn_button(
+                        parent,
+                        ResetBindingsButton,
+                        "controls.reset",
+                        default_font.clone(),
+                        UiRect::ZERO,
+                    );
+                    spawn_button(
+                        parent,
+                        BackButton,
+                        "common.back",
                         default_font.clone(),
                         UiRect::ZERO,
                     );
@@ -72,19 +471,343 @@ fn spawn_start_menu_page(mut commands: Commands, default_font: Res<AppDefaultFon
         });
 }
 
-// 按下开始游戏按钮
+// 生成设置页面，每个设置项是一个名字和一个切换按钮，按钮文本由 update_setting_text 填写
+fn spawn_settings_page(mut commands: Commands, default_font: Res<AppDefaultFont>) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    display: Display::Grid,
+                    grid_template_columns: RepeatedGridTrack::auto(3),
+                    width: Val::Percent(100.),
+                    height: Val::Percent(100.),
+                    justify_content: JustifyContent::Center,
+                    align_content: AlignContent::Center,
+                    justify_items: JustifyItems::Center,
+                    align_items: AlignItems::End,
+                    column_gap: Val::Px(40.),
+                    row_gap: Val::Px(20.),
+                    ..default()
+                },
+                background_color: BackgroundColor(tailwind::GRAY_600.into()),
+                ..default()
+            },
+            StateScoped(StartMenuPage::Settings),
+        ))
+        .with_children(|parent| {
+            for kind in SettingKind::ALL {
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Column,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        parent.spawn((
+                            TextBundle::from_section(
+                                "",
+                                TextStyle {
+                                    font: default_font.clone(),
+                                    font_size: 50.,
+                                    color: Color::WHITE,
+                                },
+                            ),
+                            LocalizedText::key(kind.label()),
+                        ));
+                        spawn_button(
+                            parent,
+                            SettingButton(kind),
+                            "",
+                            default_font.clone(),
+                            UiRect::top(Val::Px(10.)),
+                        );
+                    });
+            }
+            spawn_button(
+                parent,
+                BackButton,
+                "common.back",
+                default_font.clone(),
+                UiRect::ZERO,
+            );
+        });
+}
+
+// 生成按键绑定按钮，文本由 update_binding_text 填写
+fn spawn_binding_button(
+    parent: &mut ChildBuilder,
+    binding_button: BindingButton,
+    style: TextStyle,
+) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    width: Val::Px(300.),
+                    height: Val::Px(80.),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    border: UiRect::all(Val::Px(6.)),
+                    ..default()
+                },
+                background_color: BackgroundColor(BUTTON_NONE),
+                border_color: BorderColor(Color::BLACK),
+                border_radius: BorderRadius::all(Val::Percent(25.)),
+                ..default()
+            },
+            binding_button,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section("", style),
+                LocalizedText::literal(""),
+            ));
+        });
+}
+
+// 按下继续游戏按钮，加载存档的关卡，进入 GamePhase::Playing 时恢复进度
+fn pressed_continue_button(
+    mut commands: Commands,
+    button: Query<&Interaction, (Changed<Interaction>, With<ContinueButton>)>,
+    mut current_level: ResMut<CurrentLevel>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let Ok(interaction) = button.get_single() else {
+        return;
+    };
+    if let Interaction::Pressed = interaction {
+        let Some(save_state) = SaveState::load() else {
+            return;
+        };
+        *current_level = CurrentLevel(save_state.level);
+        commands.insert_resource(PendingSave(save_state));
+        next_state.set(AppState::Game);
+    }
+}
+
+// 按下开始游戏按钮，从第一关开始
 fn pressed_start_game_button(
     button: Query<&Interaction, (Changed<Interaction>, With<StartGameButton>)>,
+    mut current_level: ResMut<CurrentLevel>,
     mut next_state: ResMut<NextState<AppState>>,
 ) {
     let Ok(interaction) = button.get_single() else {
         return;
     };
     if let Interaction::Pressed = interaction {
+        *current_level = CurrentLevel::default();
         next_state.set(AppState::Game);
     }
 }
 
+// 按下选择关卡按钮
+fn pressed_level_select_button(
+    button: Query<&Interaction, (Changed<Interaction>, With<LevelSelectButton>)>,
+    mut next_state: ResMut<NextState<StartMenuPage>>,
+) {
+    let Ok(interaction) = button.get_single() else {
+        return;
+    };
+    if let Interaction::Pressed = interaction {
+        next_state.set(StartMenuPage::LevelSelect);
+    }
+}
+
+// 按下排行榜按钮
+fn pressed_high_scores_button(
+    button: Query<&Interaction, (Changed<Interaction>, With<HighScoresButton>)>,
+    mut next_state: ResMut<NextState<StartMenuPage>>,
+) {
+    let Ok(interaction) = button.get_single() else {
+        return;
+    };
+    if let Interaction::Pressed = interaction {
+        next_state.set(StartMenuPage::HighScores);
+    }
+}
+
+// 按下按键设置按钮
+fn pressed_controls_button(
+    button: Query<&Interaction, (Changed<Interaction>, With<ControlsButton>)>,
+    mut next_state: ResMut<NextState<StartMenuPage>>,
+) {
+    let Ok(interaction) = button.get_single() else {
+        return;
+    };
+    if let Interaction::Pressed = interaction {
+        next_state.set(StartMenuPage::Controls);
+    }
+}
+
+// 按下设置按钮
+fn pressed_settings_button(
+    button: Query<&Interaction, (Changed<Interaction>, With<SettingsButton>)>,
+    mut next_state: ResMut<NextState<StartMenuPage>>,
+) {
+    let Ok(interaction) = button.get_single() else {
+        return;
+    };
+    if let Interaction::Pressed = interaction {
+        next_state.set(StartMenuPage::Settings);
+    }
+}
+
+// 按下设置项按钮，切换到下一个值并保存
+fn pressed_setting_button(
+    buttons: Query<(&Interaction, &SettingButton), Changed<Interaction>>,
+    mut settings: ResMut<GameSettings>,
+) {
+    for (interaction, setting_button) in &buttons {
+        if let Interaction::Pressed = interaction {
+            settings.cycle(setting_button.0);
+            settings.save();
+        }
+    }
+}
+
+// 更新设置项按钮的文本
+fn update_setting_text(
+    buttons: Query<(Ref<SettingButton>, &Children)>,
+    mut texts: Query<&mut LocalizedText>,
+    settings: Res<GameSettings>,
+) {
+    for (setting_button, children) in &buttons {
+        if !settings.is_changed() && !setting_button.is_added() {
+            continue;
+        }
+        let Some(mut text) = children
+            .first()
+            .and_then(|&child| texts.get_mut(child).ok())
+        else {
+            continue;
+        };
+        text.set_if_neq(settings.value_text(setting_button.0));
+    }
+}
+
+// 按下按键绑定按钮，等待新的按键
+fn pressed_binding_button(
+    mut commands: Commands,
+    buttons: Query<(&Interaction, &BindingButton), Changed<Interaction>>,
+) {
+    for (interaction, binding_button) in &buttons {
+        if let Interaction::Pressed = interaction {
+            commands.insert_resource(Rebinding(*binding_button));
+        }
+    }
+}
+
+// 读取新的按键并保存，按下 Escape 取消
+fn capture_binding(
+    mut commands: Commands,
+    rebinding: Res<Rebinding>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    mut bindings: ResMut<InputBindings>,
+) {
+    if keyboard.just_pressed(KeyCode::Escape) {
+        commands.remove_resource::<Rebinding>();
+        return;
+    }
+    match rebinding.device {
+        BindingDevice::Keyboard => {
+            let Some(&key) = keyboard.get_just_pressed().next() else {
+                return;
+            };
+            bindings.bind_key(rebinding.action, key);
+        }
+        BindingDevice::Gamepad => {
+            let Some(gamepad_button) = gamepad_buttons.get_just_pressed().next() else {
+                return;
+            };
+            bindings.bind_gamepad_button(rebinding.action, gamepad_button.button_type);
+        }
+    }
+    bindings.save();
+    commands.remove_resource::<Rebinding>();
+}
+
+// 按下恢复默认按键按钮
+fn pressed_reset_bindings_button(
+    mut commands: Commands,
+    button: Query<&Interaction, (Changed<Interaction>, With<ResetBindingsButton>)>,
+    mut bindings: ResMut<InputBindings>,
+) {
+    let Ok(interaction) = button.get_single() else {
+        return;
+    };
+    if let Interaction::Pressed = interaction {
+        *bindings = InputBindings::defaults();
+        bindings.save();
+        commands.remove_resource::<Rebinding>();
+    }
+}
+
+// 更新按键绑定按钮的文本，等待新按键的按钮显示提示
+fn update_binding_text(
+    buttons: Query<(&BindingButton, &Children)>,
+    mut texts: Query<&mut LocalizedText>,
+    bindings: Res<InputBindings>,
+    rebinding: Option<Res<Rebinding>>,
+) {
+    for (binding_button, children) in &buttons {
+        let Some(mut text) = children
+            .first()
+            .and_then(|&child| texts.get_mut(child).ok())
+        else {
+            continue;
+        };
+        let binding = bindings.get(binding_button.action);
+        let value = if rebinding
+            .as_ref()
+            .is_some_and(|rebinding| ***rebinding == *binding_button)
+        {
+            LocalizedText::key("controls.press_key")
+        } else {
+            LocalizedText::literal(match binding_button.device {
+                BindingDevice::Keyboard => key_name(binding.key),
+                BindingDevice::Gamepad => gamepad_button_name(binding.gamepad_button),
+            })
+        };
+        text.set_if_neq(value);
+    }
+}
+
+// 离开按键设置页面时取消等待
+fn remove_rebinding(mut commands: Commands) {
+    commands.remove_resource::<Rebinding>();
+}
+
+// 按下关卡按钮，从这一关开始
+fn pressed_level_button(
+    buttons: Query<(&Interaction, &LevelButton), Changed<Interaction>>,
+    mut current_level: ResMut<CurrentLevel>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    for (interaction, level_button) in &buttons {
+        if let Interaction::Pressed = interaction {
+            *current_level = CurrentLevel(level_button.0);
+            next_state.set(AppState::Game);
+        }
+    }
+}
+
+// 按下返回按钮
+fn pressed_back_button(
+    button: Query<&Interaction, (Changed<Interaction>, With<BackButton>)>,
+    mut next_state: ResMut<NextState<StartMenuPage>>,
+) {
+    let Ok(interaction) = button.get_single() else {
+        return;
+    };
+    if let Interaction::Pressed = interaction {
+        next_state.set(StartMenuPage::Main);
+    }
+}
+
 // 按下退出游戏按钮
 fn pressed_exit_game_button(
     button: Query<&Interaction, (Changed<Interaction>, With<ExitGameButton>)>,
@@ -0,0 +1,72 @@
+use std::{fs, path::PathBuf};
+
+use bevy::{asset::io::file::FileAssetReader, prelude::*, scene::ron};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::ASSETS_PATH;
+
+// 本地存档目录，位于工作目录下
+const DATA_DIR: &str = "tiny_blue_data";
+
+// 读取资产目录中的 RON 文件
+pub fn load_asset_ron<T: DeserializeOwned>(file_name: &str) -> Result<T, String> {
+    let path = FileAssetReader::get_base_path()
+        .join(ASSETS_PATH)
+        .join(file_name);
+    let ron_string = fs::read_to_string(&path).map_err(|error| error.to_string())?;
+    ron::from_str(&ron_string).map_err(|error| error.to_string())
+}
+
+// 读取本地存档，文件不存在或无法解析时使用默认值
+pub fn load_ron<T: DeserializeOwned + Default>(file_name: &str) -> T {
+    try_load_ron(file_name).unwrap_or_default()
+}
+
+// 读取本地存档，文件不存在或无法解析时返回 None
+pub fn try_load_ron<T: DeserializeOwned>(file_name: &str) -> Option<T> {
+    let path = data_path(file_name);
+    let ron_string = fs::read_to_string(&path).ok()?;
+    ron::from_str(&ron_string)
+        .map_err(|error| warn!("{} parse failed: {error}", path.display()))
+        .ok()
+}
+
+// 保存本地存档
+pub fn save_ron<T: Serialize>(file_name: &str, value: &T) {
+    let ron_string = ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default())
+        .map_err(|error| error.to_string());
+    write_data(file_name, ron_string);
+}
+
+// 保存 JSON 文件到本地存档目录，file_name 可以包含子目录
+pub fn save_json<T: Serialize>(file_name: &str, value: &T) {
+    let json_string = serde_json::to_string_pretty(value).map_err(|error| error.to_string());
+    write_data(file_name, json_string);
+}
+
+fn write_data(file_name: &str, contents: Result<String, String>) {
+    let path = data_path(file_name);
+    let result = contents.and_then(|contents| {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|error| error.to_string())?;
+        }
+        fs::write(&path, contents).map_err(|error| error.to_string())
+    });
+    if let Err(error) = result {
+        error!("{} save failed: {error}", path.display());
+    }
+}
+
+// 删除本地存档
+pub fn remove_data(file_name: &str) {
+    let path = data_path(file_name);
+    if let Err(error) = fs::remove_file(&path) {
+        if error.kind() != std::io::ErrorKind::NotFound {
+            error!("{} remove failed: {error}", path.display());
+        }
+    }
+}
+
+fn data_path(file_name: &str) -> PathBuf {
+    PathBuf::from(DATA_DIR).join(file_name)
+}